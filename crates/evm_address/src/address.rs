@@ -57,27 +57,43 @@ impl str::FromStr for EvmAddress {
             .strip_prefix("0x")
             .ok_or(EAddressError::wrong_format())?;
         (0..BYTE_SIZE)
-            .fold(Ok([0u8; BYTE_SIZE]), |prev, index| {
-                prev.and_then(|mut bs| {
-                    let i = index * 2;
-                    let hex = &hexes[i..(i + 2)];
-                    bs[index] = u8::from_str_radix(hex, 16)?;
-                    Ok(bs)
-                })
+            .try_fold([0u8; BYTE_SIZE], |mut bs, index| {
+                let i = index * 2;
+                let hex = &hexes[i..(i + 2)];
+                bs[index] = u8::from_str_radix(hex, 16)?;
+                Ok(bs)
             })
             .map(EvmAddress)
     }
 }
 
-impl From<PubKeyBytes> for EvmAddress {
-    fn from(src: PubKeyBytes) -> Self {
-        let data = src.uncompressed_bytes();
+impl AsRef<[u8]> for EvmAddress {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl TryFrom<&[u8]> for EvmAddress {
+    type Error = EAddressError;
+
+    fn try_from(src: &[u8]) -> Result<Self, Self::Error> {
+        src.try_into()
+            .map(EvmAddress)
+            .map_err(|_| EAddressError::wrong_format())
+    }
+}
+
+impl TryFrom<PubKeyBytes> for EvmAddress {
+    type Error = EAddressError;
+
+    fn try_from(src: PubKeyBytes) -> Result<Self, Self::Error> {
+        let data = src.uncompressed_bytes()?;
         let mut keccak = Keccak256::new();
         keccak.update(&data[1..]);
         let result32 = keccak.finalize();
         let drop = result32.len() - BYTE_SIZE;
         let array = &result32[drop..];
-        Self(array.try_into().expect("Should be 20 bytes"))
+        Ok(Self(array.try_into().expect("Should be 20 bytes")))
     }
 }
 
@@ -102,9 +118,9 @@ mod test {
         let hdpath = "m/44'/60'/0'/0/0";
 
         let seed = mnemonic::calcseed::to_seed(&words).unwrap();
-        let m = ExtPrvKey::from_seed(Prefix::XPRV, seed).unwrap();
+        let m = ExtPrvKey::from_seed(Prefix::XPRV, &seed).unwrap();
         let prvkey = m.derive_child(hdpath.parse().unwrap()).unwrap();
-        let ea: EvmAddress = prvkey.get_key().get_public().unwrap().into();
+        let ea: EvmAddress = prvkey.get_key().get_public().unwrap().try_into().unwrap();
         assert_eq!(address, ea.to_string());
     }
 
@@ -1,4 +1,6 @@
+use core::fmt;
 use core::num::ParseIntError;
+use extend_key::ExtendError;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct EAddressError(String);
@@ -7,6 +9,24 @@ impl EAddressError {
     pub fn wrong_format() -> EAddressError {
         EAddressError("Wrong format address".to_owned())
     }
+
+    pub fn wrong_password() -> EAddressError {
+        EAddressError("Wrong password or broken keystore".to_owned())
+    }
+
+    pub fn address_mismatched() -> EAddressError {
+        EAddressError("Address does not match the key".to_owned())
+    }
+
+    pub fn unsupported(target: &str) -> EAddressError {
+        EAddressError(format!("Unsupported {target}"))
+    }
+}
+
+impl fmt::Display for EAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
 }
 
 impl From<ParseIntError> for EAddressError {
@@ -14,3 +34,23 @@ impl From<ParseIntError> for EAddressError {
         EAddressError(src.to_string())
     }
 }
+
+impl From<ExtendError> for EAddressError {
+    fn from(src: ExtendError) -> Self {
+        EAddressError(src.to_string())
+    }
+}
+
+#[cfg(feature = "keystore")]
+impl From<serde_json::Error> for EAddressError {
+    fn from(src: serde_json::Error) -> Self {
+        EAddressError(src.to_string())
+    }
+}
+
+#[cfg(feature = "keystore")]
+impl From<getrandom::Error> for EAddressError {
+    fn from(src: getrandom::Error) -> Self {
+        EAddressError(src.to_string())
+    }
+}
@@ -0,0 +1,505 @@
+use crate::address::EvmAddress;
+use crate::error::EAddressError;
+use aes::Aes128;
+use core::{fmt, str};
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use extend_key::ecdsa_key::{PrvKey, PrvKeyBytes};
+use hmac::Hmac;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+const VERSION: u32 = 3;
+const CIPHER: &str = "aes-128-ctr";
+const PRF: &str = "hmac-sha256";
+const DK_LEN: usize = 32;
+const SALT_SIZE: usize = 32;
+pub const IV_SIZE: usize = 16;
+/// Max memory of scrypt in bytes, `128 * r * n * p`, as the parameters come from the file.
+const MAX_SCRYPT_MEMORY: u128 = 1 << 30;
+/// Max iterations of PBKDF2, as the parameters come from the file.
+const MAX_PBKDF2_ROUNDS: u32 = 10_000_000;
+
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+
+/// Web3 Secret Storage (keystore v3) of a private key, as written by geth.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    #[serde(default, with = "hex_address", skip_serializing_if = "Option::is_none")]
+    address: Option<EvmAddress>,
+    #[serde(alias = "Crypto")]
+    crypto: Crypto,
+    id: String,
+    version: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Crypto {
+    cipher: String,
+    cipherparams: CipherParams,
+    #[serde(with = "hex")]
+    ciphertext: Vec<u8>,
+    #[serde(flatten)]
+    kdf: Kdf,
+    #[serde(with = "hex")]
+    mac: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CipherParams {
+    #[serde(with = "hex")]
+    iv: Vec<u8>,
+}
+
+/// Key derivation function with its parameters, `kdf` and `kdfparams` in the file.
+///
+/// scrypt must satisfy `n < 2^(16 * r)` of RFC 7914, which the scrypt crate enforces
+/// but geth does not. Files with a small `r` such as the Web3 Secret Storage test
+/// vector (n = 2^18, r = 1, p = 8) can not be decrypted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kdf", content = "kdfparams", rename_all = "lowercase")]
+pub enum Kdf {
+    Scrypt(ScryptParams),
+    Pbkdf2(Pbkdf2Params),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScryptParams {
+    pub dklen: usize,
+    pub n: u32,
+    pub r: u32,
+    pub p: u32,
+    #[serde(with = "hex")]
+    pub salt: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pbkdf2Params {
+    pub c: u32,
+    pub dklen: usize,
+    pub prf: String,
+    #[serde(with = "hex")]
+    pub salt: Vec<u8>,
+}
+
+impl Kdf {
+    /// scrypt with the parameters of geth (n = 2^18, r = 8, p = 1) and a random salt.
+    pub fn scrypt() -> Result<Self, EAddressError> {
+        Self::scrypt_with(1 << 18, 8, 1)
+    }
+
+    pub fn scrypt_with(n: u32, r: u32, p: u32) -> Result<Self, EAddressError> {
+        Ok(Self::Scrypt(ScryptParams {
+            dklen: DK_LEN,
+            n,
+            r,
+            p,
+            salt: random::<SALT_SIZE>()?.to_vec(),
+        }))
+    }
+
+    /// PBKDF2-HMAC-SHA256 with `c` iterations and a random salt.
+    pub fn pbkdf2(c: u32) -> Result<Self, EAddressError> {
+        Ok(Self::Pbkdf2(Pbkdf2Params {
+            c,
+            dklen: DK_LEN,
+            prf: PRF.to_owned(),
+            salt: random::<SALT_SIZE>()?.to_vec(),
+        }))
+    }
+
+    fn derive(&self, password: &[u8]) -> Result<Zeroizing<[u8; DK_LEN]>, EAddressError> {
+        let mut dk = Zeroizing::new([0; DK_LEN]);
+        match self {
+            Self::Scrypt(params) => {
+                // Parameters out of RFC 7914 (n >= 2^(16 * r)) are rejected by the scrypt crate
+                if params.dklen != DK_LEN || !params.n.is_power_of_two() {
+                    return Err(EAddressError::unsupported("scrypt parameters"));
+                }
+                let memory = 128 * params.r as u128 * params.n as u128 * params.p as u128;
+                if memory > MAX_SCRYPT_MEMORY {
+                    return Err(EAddressError::unsupported("scrypt cost"));
+                }
+                let log_n = params.n.trailing_zeros() as u8;
+                let p = scrypt::Params::new(log_n, params.r, params.p)
+                    .map_err(|_| EAddressError::unsupported("scrypt parameters"))?;
+                scrypt::scrypt(password, &params.salt, &p, dk.as_mut())
+                    .map_err(|_| EAddressError::unsupported("scrypt parameters"))?;
+            }
+            Self::Pbkdf2(params) => {
+                if params.dklen != DK_LEN || params.prf != PRF {
+                    return Err(EAddressError::unsupported("pbkdf2 parameters"));
+                }
+                if params.c > MAX_PBKDF2_ROUNDS {
+                    return Err(EAddressError::unsupported("pbkdf2 cost"));
+                }
+                pbkdf2::pbkdf2::<Hmac<Sha256>>(password, &params.salt, params.c, dk.as_mut());
+            }
+        }
+        Ok(dk)
+    }
+}
+
+/// Keccak-256 of the second half of the derived key and the cipher text.
+fn mac(dk: &[u8; DK_LEN], ciphertext: &[u8]) -> [u8; 32] {
+    let mut keccak = Keccak256::new();
+    keccak.update(&dk[DK_LEN / 2..]);
+    keccak.update(ciphertext);
+    keccak.finalize().into()
+}
+
+/// AES-128-CTR with the first half of the derived key, which encrypts and decrypts alike.
+fn apply_cipher(dk: &[u8; DK_LEN], iv: &[u8], data: &mut [u8]) -> Result<(), EAddressError> {
+    let mut cipher = Aes128Ctr::new_from_slices(&dk[..DK_LEN / 2], iv)
+        .map_err(|_| EAddressError::wrong_format())?;
+    cipher.apply_keystream(data);
+    Ok(())
+}
+
+fn random<const N: usize>() -> Result<[u8; N], EAddressError> {
+    let mut buf = [0; N];
+    getrandom::getrandom(&mut buf)?;
+    Ok(buf)
+}
+
+/// Random UUID (version 4) for the `id`.
+fn uuid_v4() -> Result<String, EAddressError> {
+    let mut bs = random::<16>()?;
+    bs[6] = (bs[6] & 0x0f) | 0x40;
+    bs[8] = (bs[8] & 0x3f) | 0x80;
+    let h = hex::encode(&bs);
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &h[..8],
+        &h[8..12],
+        &h[12..16],
+        &h[16..20],
+        &h[20..]
+    ))
+}
+
+impl Keystore {
+    /// Encrypts the key with a random IV and id.
+    pub fn encrypt(key: &PrvKeyBytes, password: &[u8], kdf: Kdf) -> Result<Self, EAddressError> {
+        Self::encrypt_with(key, password, kdf, &random()?, &uuid_v4()?)
+    }
+
+    /// `encrypt` with the given IV and id. The IV must not be reused with the same salt.
+    pub fn encrypt_with(
+        key: &PrvKeyBytes,
+        password: &[u8],
+        kdf: Kdf,
+        iv: &[u8; IV_SIZE],
+        id: &str,
+    ) -> Result<Self, EAddressError> {
+        let address = EvmAddress::try_from(key.get_public()?)?;
+        let dk = kdf.derive(password)?;
+        let mut ciphertext = key.as_ref().to_vec();
+        apply_cipher(&dk, iv, &mut ciphertext)?;
+        Ok(Self {
+            address: Some(address),
+            crypto: Crypto {
+                cipher: CIPHER.to_owned(),
+                cipherparams: CipherParams { iv: iv.to_vec() },
+                mac: mac(&dk, &ciphertext).to_vec(),
+                ciphertext,
+                kdf,
+            },
+            id: id.to_owned(),
+            version: VERSION,
+        })
+    }
+
+    /// Decrypts the key, which must match the stored address if any.
+    /// Fails with `unsupported` for scrypt parameters out of RFC 7914, see `Kdf`.
+    pub fn decrypt(&self, password: &[u8]) -> Result<PrvKeyBytes, EAddressError> {
+        if self.version != VERSION {
+            return Err(EAddressError::unsupported("keystore version"));
+        }
+        if self.crypto.cipher != CIPHER {
+            return Err(EAddressError::unsupported("cipher"));
+        }
+        let dk = self.crypto.kdf.derive(password)?;
+        let mac = mac(&dk, &self.crypto.ciphertext);
+        if !bool::from(mac.ct_eq(self.crypto.mac.as_slice())) {
+            return Err(EAddressError::wrong_password());
+        }
+        let mut plain = Zeroizing::new(self.crypto.ciphertext.clone());
+        apply_cipher(&dk, &self.crypto.cipherparams.iv, &mut plain)?;
+        let key: PrvKeyBytes = plain.as_slice().try_into()?;
+        let address = EvmAddress::try_from(key.get_public()?)?;
+        if self.address.as_ref().is_some_and(|a| *a != address) {
+            return Err(EAddressError::address_mismatched());
+        }
+        Ok(key)
+    }
+
+    pub fn address(&self) -> Option<&EvmAddress> {
+        self.address.as_ref()
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn kdf(&self) -> &Kdf {
+        &self.crypto.kdf
+    }
+}
+
+impl fmt::Display for Keystore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&serde_json::to_string(self).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl str::FromStr for Keystore {
+    type Err = EAddressError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_str(src)?)
+    }
+}
+
+/// Lower case hex without `0x`, as the fields of keystore files.
+mod hex {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn encode(bs: &[u8]) -> String {
+        bs.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    pub fn decode(src: &str) -> Option<Vec<u8>> {
+        let src = src.strip_prefix("0x").unwrap_or(src);
+        if !src.len().is_multiple_of(2) || !src.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        (0..src.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(src.get(i..i + 2)?, 16).ok())
+            .collect()
+    }
+
+    pub fn serialize<S: Serializer>(bs: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode(bs))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        decode(&s).ok_or_else(|| de::Error::custom("invalid hex"))
+    }
+}
+
+mod hex_address {
+    use super::hex;
+    use crate::address::EvmAddress;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        address: &Option<EvmAddress>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match address {
+            Some(a) => hex::serialize(a.as_ref(), serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<EvmAddress>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| {
+                let bs = hex::decode(&s).ok_or_else(|| de::Error::custom("invalid hex"))?;
+                EvmAddress::try_from(bs.as_slice()).map_err(de::Error::custom)
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    const PASSWORD: &[u8] = b"testpassword";
+
+    fn key() -> PrvKeyBytes {
+        hex!("7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d")
+            .as_ref()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn web3_pbkdf2() {
+        let json = r#"{
+            "crypto" : {
+                "cipher" : "aes-128-ctr",
+                "cipherparams" : {
+                    "iv" : "6087dab2f9fdbbfaddc31a909735c1e6"
+                },
+                "ciphertext" : "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                "kdf" : "pbkdf2",
+                "kdfparams" : {
+                    "c" : 262144,
+                    "dklen" : 32,
+                    "prf" : "hmac-sha256",
+                    "salt" : "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                },
+                "mac" : "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+            },
+            "id" : "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+            "version" : 3
+        }"#;
+        let keystore: Keystore = json.parse().unwrap();
+        assert_eq!(keystore.decrypt(PASSWORD).unwrap(), key());
+        assert!(keystore.decrypt(b"wrong").is_err());
+        assert_eq!(keystore.to_string().parse::<Keystore>().unwrap(), keystore);
+    }
+
+    #[test]
+    fn web3_scrypt_small_r() {
+        let json = r#"{
+            "Crypto" : {
+                "cipher" : "aes-128-ctr",
+                "cipherparams" : {
+                    "iv" : "83dbcc02d8ccb40e466191a123791e0e"
+                },
+                "ciphertext" : "d172bf743a674da9cdad04534d56926ef8358534d458fffccd4e6ad2fbde479c",
+                "kdf" : "scrypt",
+                "kdfparams" : {
+                    "dklen" : 32,
+                    "n" : 262144,
+                    "p" : 8,
+                    "r" : 1,
+                    "salt" : "ab0c7876052600dd703518d6fc3fe8984592145b591fc8fb5c6d43190334ba19"
+                },
+                "mac" : "2103ac29920d71da29f15d75b4a16dbe95cfd7ff8faea1056c33131d846e3097"
+            },
+            "id" : "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+            "version" : 3
+        }"#;
+        // r = 1 is too small for n = 2^18 by RFC 7914, which the scrypt crate enforces
+        let keystore: Keystore = json.parse().unwrap();
+        assert_eq!(
+            keystore.decrypt(PASSWORD),
+            Err(EAddressError::unsupported("scrypt parameters"))
+        );
+    }
+
+    /// Written by another client with the standard r = 8 and p = 1.
+    #[test]
+    fn scrypt_r8() {
+        let json = r#"{
+            "version" : 3,
+            "id" : "3b330c3b-b0b3-4e39-b62e-c2041a98d673",
+            "address" : "4c8ab9d3e938285776d6717d7319f6a9b1d809dd",
+            "Crypto" : {
+                "ciphertext" : "bb3a6dbf21f0bf2b5eb0b43426590f16650acee9462ab710cca18781691a5739",
+                "cipherparams" : {
+                    "iv" : "6a533f77fc5cb8a752a16ec6a3200da1"
+                },
+                "cipher" : "aes-128-ctr",
+                "kdf" : "scrypt",
+                "kdfparams" : {
+                    "dklen" : 32,
+                    "salt" : "a58609853dec53c81feb165e346c700e714285771825bb4cbf87c4ea1996b682",
+                    "n" : 8192,
+                    "r" : 8,
+                    "p" : 1
+                },
+                "mac" : "a71edeb659ed628db13579ce9f75c80c9d386c1239b280548d9a0e58ad20d6c7"
+            }
+        }"#;
+        let keystore: Keystore = json.parse().unwrap();
+        let key = keystore.decrypt(b"123456789").unwrap();
+        let address: EvmAddress = "0x4C8aB9d3e938285776d6717d7319F6a9B1d809DD"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            EvmAddress::try_from(key.get_public().unwrap()).unwrap(),
+            address
+        );
+        assert_eq!(
+            keystore.decrypt(PASSWORD),
+            Err(EAddressError::wrong_password())
+        );
+    }
+
+    #[test]
+    fn cost_limits() {
+        // 128 * r * n * p is 2 GiB
+        let kdf = Kdf::scrypt_with(1 << 20, 8, 2).unwrap();
+        assert_eq!(
+            Keystore::encrypt(&key(), PASSWORD, kdf),
+            Err(EAddressError::unsupported("scrypt cost"))
+        );
+        let kdf = Kdf::scrypt_with(1 << 16, u32::MAX, u32::MAX).unwrap();
+        assert_eq!(
+            kdf.derive(PASSWORD).err(),
+            Some(EAddressError::unsupported("scrypt cost"))
+        );
+        let kdf = Kdf::pbkdf2(MAX_PBKDF2_ROUNDS + 1).unwrap();
+        assert_eq!(
+            kdf.derive(PASSWORD).err(),
+            Some(EAddressError::unsupported("pbkdf2 cost"))
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for kdf in [Kdf::scrypt_with(1 << 10, 8, 1), Kdf::pbkdf2(1000)] {
+            let keystore = Keystore::encrypt(&key(), PASSWORD, kdf.unwrap()).unwrap();
+            let address: EvmAddress = "0x008AeEda4D805471dF9b2A5B0f38A0C3bCBA786b"
+                .parse()
+                .unwrap();
+            assert_eq!(keystore.address(), Some(&address));
+
+            let json = keystore.to_string();
+            assert!(json.contains(&hex::encode(address.as_ref())), "{json}");
+            let parsed: Keystore = json.parse().unwrap();
+            assert_eq!(parsed, keystore);
+            assert_eq!(parsed.decrypt(PASSWORD).unwrap(), key());
+            assert!(parsed.decrypt(b"wrong").is_err());
+        }
+    }
+
+    #[test]
+    fn address_mismatched() {
+        let kdf = Kdf::pbkdf2(1000).unwrap();
+        let mut keystore =
+            Keystore::encrypt_with(&key(), PASSWORD, kdf, &[0; IV_SIZE], "id").unwrap();
+        keystore.address = Some(
+            "0x0000000000000000000000000000000000000001"
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(
+            keystore.decrypt(PASSWORD),
+            Err(EAddressError::address_mismatched())
+        );
+        keystore.address = None;
+        assert_eq!(keystore.decrypt(PASSWORD).unwrap(), key());
+    }
+
+    #[test]
+    fn unsupported() {
+        let kdf = Kdf::pbkdf2(1000).unwrap();
+        let keystore = Keystore::encrypt(&key(), PASSWORD, kdf).unwrap();
+        let json = keystore.to_string().replace("hmac-sha256", "hmac-sha512");
+        let keystore: Keystore = json.parse().unwrap();
+        assert!(keystore.decrypt(PASSWORD).is_err());
+        assert!("{}".parse::<Keystore>().is_err());
+    }
+
+    #[test]
+    fn hex_digits() {
+        assert_eq!(hex::decode("0x0aFf"), Some(vec![0x0a, 0xff]));
+        assert_eq!(hex::decode("+a0b"), None);
+        assert_eq!(hex::decode("0a0"), None);
+        assert_eq!(hex::decode("0g"), None);
+    }
+}
@@ -1,2 +1,7 @@
 pub mod address;
 pub mod error;
+#[cfg(feature = "keystore")]
+pub mod keystore;
+
+#[cfg(feature = "serde")]
+mod serde_impl;
@@ -0,0 +1,73 @@
+use crate::address::EvmAddress;
+use core::fmt;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+impl Serialize for EvmAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(self.as_ref())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for EvmAddress {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AddressVisitor;
+
+        impl<'de> de::Visitor<'de> for AddressVisitor {
+            type Value = EvmAddress;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("hex address or 20 bytes")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                v.try_into().map_err(E::custom)
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bs = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+                while let Some(b) = seq.next_element()? {
+                    bs.push(b);
+                }
+                self.visit_bytes(&bs)
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(AddressVisitor)
+        } else {
+            deserializer.deserialize_bytes(AddressVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+    use serde_test::{assert_de_tokens, assert_tokens, Configure, Token};
+
+    #[test]
+    fn address() {
+        let src = "0x46718B1e73047a691c259995ed135f4933214f2c";
+        let ea: EvmAddress = src.parse().unwrap();
+        assert_tokens(&ea.clone().readable(), &[Token::Str(src)]);
+        assert_de_tokens(
+            &ea.clone().readable(),
+            &[Token::Str("0x46718b1e73047a691c259995ed135f4933214f2c")],
+        );
+        assert_tokens(
+            &ea.compact(),
+            &[Token::Bytes(&hex!(
+                "46718B1e73047a691c259995ed135f4933214f2c"
+            ))],
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use extend_key::base58::Prefix;
+use extend_key::ecdsa_key::{PrvKeyBytes, PubKeyBytes};
+use extend_key::extkey::ExtKey;
+use hdpath::node::Node;
+
+const COUNT: u32 = 1000;
+
+fn account() -> ExtKey<PubKeyBytes> {
+    let seed = (0..16u8).collect::<Vec<_>>();
+    let m: ExtKey<PrvKeyBytes> = ExtKey::from_seed(Prefix::XPRV, &seed).unwrap();
+    m.derive_child("m/44'/60'/0'/0".parse().unwrap())
+        .unwrap()
+        .get_public()
+        .unwrap()
+}
+
+fn derive_range(c: &mut Criterion) {
+    let xpub = account();
+    let mut group = c.benchmark_group("derive 1000 children");
+    group.sample_size(10);
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            (0..COUNT)
+                .map(|i| xpub.get_child_normal_only(Node::Normal(i)).unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("range", |b| {
+        b.iter(|| xpub.get_children_normal_only(0..COUNT).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, derive_range);
+criterion_main!(benches);
@@ -0,0 +1,144 @@
+use crate::base58::{self, DecodedExtKey, Prefix};
+use crate::curve::{EcdsaCurve, Secp256k1};
+use crate::ecdsa_key::{Fingerprint, PrvKey, PrvKeyBytes, PubKey, PubKeyBytes};
+use crate::extkey::{ChainCode, ChildNumber, Depth, ExtKey};
+use crate::ExtendError;
+use core::fmt;
+use core::str::FromStr;
+
+/// Extended key of either kind, chosen by the version prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnyExtKey<C: EcdsaCurve = Secp256k1> {
+    Private(ExtKey<PrvKeyBytes<C>>),
+    Public(ExtKey<PubKeyBytes<C>>),
+}
+
+impl<C: EcdsaCurve> AnyExtKey<C> {
+    pub fn is_private(&self) -> bool {
+        matches!(self, Self::Private(_))
+    }
+
+    pub fn prefix(&self) -> &Prefix {
+        match self {
+            Self::Private(k) => &k.prefix,
+            Self::Public(k) => &k.prefix,
+        }
+    }
+
+    pub fn depth(&self) -> &Depth {
+        match self {
+            Self::Private(k) => &k.depth,
+            Self::Public(k) => &k.depth,
+        }
+    }
+
+    /// Fingerprint of the parent key.
+    pub fn parent(&self) -> &Fingerprint {
+        match self {
+            Self::Private(k) => &k.parent,
+            Self::Public(k) => &k.parent,
+        }
+    }
+
+    pub fn child_number(&self) -> &ChildNumber {
+        match self {
+            Self::Private(k) => &k.child_number,
+            Self::Public(k) => &k.child_number,
+        }
+    }
+
+    pub fn chain_code(&self) -> &ChainCode {
+        match self {
+            Self::Private(k) => &k.chain_code,
+            Self::Public(k) => &k.chain_code,
+        }
+    }
+
+    pub fn public_key(&self) -> Result<PubKeyBytes<C>, ExtendError> {
+        match self {
+            Self::Private(k) => k.key.get_public(),
+            Self::Public(k) => Ok(k.key.clone()),
+        }
+    }
+
+    /// Fingerprint of this key.
+    pub fn fingerprint(&self) -> Result<Fingerprint, ExtendError> {
+        Ok(self.public_key()?.fingerprint())
+    }
+
+    /// The public form, as is if this is already public.
+    pub fn neuter(&self) -> Result<ExtKey<PubKeyBytes<C>>, ExtendError> {
+        match self {
+            Self::Private(k) => k.get_public(),
+            Self::Public(k) => Ok(k.clone()),
+        }
+    }
+}
+
+impl<C: EcdsaCurve> From<ExtKey<PrvKeyBytes<C>>> for AnyExtKey<C> {
+    fn from(src: ExtKey<PrvKeyBytes<C>>) -> Self {
+        Self::Private(src)
+    }
+}
+
+impl<C: EcdsaCurve> From<ExtKey<PubKeyBytes<C>>> for AnyExtKey<C> {
+    fn from(src: ExtKey<PubKeyBytes<C>>) -> Self {
+        Self::Public(src)
+    }
+}
+
+impl<C: EcdsaCurve> TryFrom<DecodedExtKey> for AnyExtKey<C> {
+    type Error = ExtendError;
+
+    fn try_from(src: DecodedExtKey) -> Result<Self, Self::Error> {
+        if src.prefix.is_private() {
+            Ok(Self::Private(src.try_into()?))
+        } else {
+            Ok(Self::Public(src.try_into()?))
+        }
+    }
+}
+
+impl<C: EcdsaCurve> FromStr for AnyExtKey<C> {
+    type Err = ExtendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        base58::decode(s)?.try_into()
+    }
+}
+
+impl<C: EcdsaCurve> fmt::Display for AnyExtKey<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Private(k) => k.fmt(f),
+            Self::Public(k) => k.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const XPRV: &str = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+    const XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+
+    #[test]
+    fn detect_kind() {
+        let xprv: AnyExtKey = XPRV.parse().unwrap();
+        let xpub: AnyExtKey = XPUB.parse().unwrap();
+        assert!(xprv.is_private());
+        assert!(!xpub.is_private());
+        assert_eq!(xprv.to_string(), XPRV);
+        assert_eq!(xpub.to_string(), XPUB);
+
+        assert_eq!(xprv.depth(), xpub.depth());
+        assert_eq!(xprv.parent(), xpub.parent());
+        assert_eq!(xprv.chain_code(), xpub.chain_code());
+        assert_eq!(xprv.public_key().unwrap(), xpub.public_key().unwrap());
+        assert_eq!(xprv.fingerprint().unwrap().to_string(), "3442193e");
+
+        assert_eq!(xprv.neuter().unwrap().to_string(), XPUB);
+        assert_eq!(xpub.neuter().unwrap().to_string(), XPUB);
+    }
+}
@@ -2,11 +2,12 @@ use crate::ecdsa_key::KEY_SIZE;
 use crate::fixed_bytes::FixedBytes;
 use crate::local_macro::fixed_bytes;
 use crate::ExtendError;
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::Bytes;
 use core::fmt;
 use core::str::FromStr;
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+use zeroize::Zeroizing;
 
 pub const ENCODED_BYTE_SIZE: usize = 78;
 pub const MAX_BASE58_SIZE: usize = 112;
@@ -15,116 +16,404 @@ pub const MAX_BASE58_SIZE: usize = 112;
 pub struct Prefix([u8; 4]);
 fixed_bytes!(Prefix);
 
-static PREFIX_PAIRS: Lazy<HashMap<Prefix, Prefix>> = Lazy::new(|| {
-    vec![(Prefix::XPRV, Prefix::XPUB), (Prefix::TPRV, Prefix::TPUB)]
-        .into_iter()
-        .collect()
+/// Script type of the addresses derived from a key, as SLIP-0132 tags each version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScriptType {
+    /// P2PKH or P2SH (BIP-0032 `xpub`)
+    P2pkh,
+    /// P2WPKH nested in P2SH (BIP-0049 `ypub`)
+    P2shP2wpkh,
+    /// Native P2WPKH (BIP-0084 `zpub`)
+    P2wpkh,
+    /// Multi-signature P2WSH nested in P2SH (`Ypub`)
+    P2shP2wsh,
+    /// Multi-signature native P2WSH (`Zpub`)
+    P2wsh,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+/// (private, public, script type, network)
+/// https://github.com/satoshilabs/slips/blob/master/slip-0132.md
+const VERSIONS: [(Prefix, Prefix, ScriptType, Network); 10] = [
+    (
+        Prefix::XPRV,
+        Prefix::XPUB,
+        ScriptType::P2pkh,
+        Network::Mainnet,
+    ),
+    (
+        Prefix::YPRV,
+        Prefix::YPUB,
+        ScriptType::P2shP2wpkh,
+        Network::Mainnet,
+    ),
+    (
+        Prefix::ZPRV,
+        Prefix::ZPUB,
+        ScriptType::P2wpkh,
+        Network::Mainnet,
+    ),
+    (
+        Prefix::YPRV_MULTI,
+        Prefix::YPUB_MULTI,
+        ScriptType::P2shP2wsh,
+        Network::Mainnet,
+    ),
+    (
+        Prefix::ZPRV_MULTI,
+        Prefix::ZPUB_MULTI,
+        ScriptType::P2wsh,
+        Network::Mainnet,
+    ),
+    (
+        Prefix::TPRV,
+        Prefix::TPUB,
+        ScriptType::P2pkh,
+        Network::Testnet,
+    ),
+    (
+        Prefix::UPRV,
+        Prefix::UPUB,
+        ScriptType::P2shP2wpkh,
+        Network::Testnet,
+    ),
+    (
+        Prefix::VPRV,
+        Prefix::VPUB,
+        ScriptType::P2wpkh,
+        Network::Testnet,
+    ),
+    (
+        Prefix::UPRV_MULTI,
+        Prefix::UPUB_MULTI,
+        ScriptType::P2shP2wsh,
+        Network::Testnet,
+    ),
+    (
+        Prefix::VPRV_MULTI,
+        Prefix::VPUB_MULTI,
+        ScriptType::P2wsh,
+        Network::Testnet,
+    ),
+];
+
+/// A private/public version pair known to `decode`, `Prefix::get_public` and `ExtKey::from_seed`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrefixPair {
+    pub label: String,
+    pub private: Prefix,
+    pub public: Prefix,
+}
+
+impl PrefixPair {
+    pub fn new(label: impl Into<String>, private: Prefix, public: Prefix) -> Self {
+        Self {
+            label: label.into(),
+            private,
+            public,
+        }
+    }
+
+    fn contains(&self, prefix: &Prefix) -> bool {
+        &self.private == prefix || &self.public == prefix
+    }
+}
+
+static REGISTRY: Lazy<RwLock<Vec<PrefixPair>>> = Lazy::new(|| {
+    RwLock::new(vec![
+        PrefixPair::new("xpub", Prefix::XPRV, Prefix::XPUB),
+        PrefixPair::new("ypub", Prefix::YPRV, Prefix::YPUB),
+        PrefixPair::new("zpub", Prefix::ZPRV, Prefix::ZPUB),
+        PrefixPair::new("Ypub", Prefix::YPRV_MULTI, Prefix::YPUB_MULTI),
+        PrefixPair::new("Zpub", Prefix::ZPRV_MULTI, Prefix::ZPUB_MULTI),
+        PrefixPair::new("tpub", Prefix::TPRV, Prefix::TPUB),
+        PrefixPair::new("upub", Prefix::UPRV, Prefix::UPUB),
+        PrefixPair::new("vpub", Prefix::VPRV, Prefix::VPUB),
+        PrefixPair::new("Upub", Prefix::UPRV_MULTI, Prefix::UPUB_MULTI),
+        PrefixPair::new("Vpub", Prefix::VPRV_MULTI, Prefix::VPUB_MULTI),
+        PrefixPair::new("Litecoin Ltub", Prefix::LTPV, Prefix::LTUB),
+        PrefixPair::new("Litecoin Mtub", Prefix::MTPV, Prefix::MTUB),
+        PrefixPair::new("Dogecoin dgub", Prefix::DGPV, Prefix::DGUB),
+        PrefixPair::new("ed25519", Prefix::ED25519_PRV, Prefix::ED25519_PUB),
+    ])
 });
 
+fn find_pair<T>(f: impl FnOnce(&PrefixPair) -> T, prefix: &Prefix) -> Option<T> {
+    let pairs = REGISTRY.read().unwrap_or_else(PoisonError::into_inner);
+    pairs.iter().find(|pair| pair.contains(prefix)).map(f)
+}
+
+/// All the registered pairs, built-in ones first.
+pub fn registered_prefixes() -> Vec<PrefixPair> {
+    REGISTRY
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Builder of prefix pairs to be registered at once.
+#[derive(Debug, Clone, Default)]
+pub struct PrefixRegistry(Vec<PrefixPair>);
+
+impl PrefixRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, label: impl Into<String>, private: Prefix, public: Prefix) -> Self {
+        self.0.push(PrefixPair::new(label, private, public));
+        self
+    }
+
+    /// Registers all the pairs, or none of them if any prefix is already used by another pair.
+    /// Registering the same pair again is not an error.
+    pub fn register(self) -> Result<(), ExtendError> {
+        let mut pairs = REGISTRY.write().unwrap_or_else(PoisonError::into_inner);
+        let mut added: Vec<PrefixPair> = vec![];
+        for pair in self.0 {
+            if pair.private == pair.public {
+                return Err(ExtendError::DuplicatedPrefix(pair.label.clone()));
+            }
+            let same = |p: &PrefixPair| p.private == pair.private && p.public == pair.public;
+            let conflict = |p: &PrefixPair| p.contains(&pair.private) || p.contains(&pair.public);
+            if pairs.iter().chain(&added).any(same) {
+                continue;
+            }
+            if pairs.iter().chain(&added).any(conflict) {
+                return Err(ExtendError::DuplicatedPrefix(pair.label.clone()));
+            }
+            added.push(pair);
+        }
+        pairs.extend(added);
+        Ok(())
+    }
+}
+
 impl Prefix {
     pub const XPRV: Prefix = Prefix([0x04, 0x88, 0xad, 0xe4]);
     pub const XPUB: Prefix = Prefix([0x04, 0x88, 0xB2, 0x1E]);
     pub const TPRV: Prefix = Prefix([0x04, 0x35, 0x83, 0x94]);
     pub const TPUB: Prefix = Prefix([0x04, 0x35, 0x87, 0xCF]);
+    pub const YPRV: Prefix = Prefix([0x04, 0x9d, 0x78, 0x78]);
+    pub const YPUB: Prefix = Prefix([0x04, 0x9d, 0x7c, 0xb2]);
+    pub const ZPRV: Prefix = Prefix([0x04, 0xb2, 0x43, 0x0c]);
+    pub const ZPUB: Prefix = Prefix([0x04, 0xb2, 0x47, 0x46]);
+    pub const UPRV: Prefix = Prefix([0x04, 0x4a, 0x4e, 0x28]);
+    pub const UPUB: Prefix = Prefix([0x04, 0x4a, 0x52, 0x62]);
+    pub const VPRV: Prefix = Prefix([0x04, 0x5f, 0x18, 0xbc]);
+    pub const VPUB: Prefix = Prefix([0x04, 0x5f, 0x1c, 0xf6]);
+    /// `Yprv`
+    pub const YPRV_MULTI: Prefix = Prefix([0x02, 0x95, 0xb0, 0x05]);
+    /// `Ypub`
+    pub const YPUB_MULTI: Prefix = Prefix([0x02, 0x95, 0xb4, 0x3f]);
+    /// `Zprv`
+    pub const ZPRV_MULTI: Prefix = Prefix([0x02, 0xaa, 0x7a, 0x99]);
+    /// `Zpub`
+    pub const ZPUB_MULTI: Prefix = Prefix([0x02, 0xaa, 0x7e, 0xd3]);
+    /// `Uprv`
+    pub const UPRV_MULTI: Prefix = Prefix([0x02, 0x42, 0x85, 0xb5]);
+    /// `Upub`
+    pub const UPUB_MULTI: Prefix = Prefix([0x02, 0x42, 0x89, 0xef]);
+    /// `Vprv`
+    pub const VPRV_MULTI: Prefix = Prefix([0x02, 0x57, 0x50, 0x48]);
+    /// `Vpub`
+    pub const VPUB_MULTI: Prefix = Prefix([0x02, 0x57, 0x54, 0x83]);
+    pub const LTPV: Prefix = Prefix([0x01, 0x9d, 0x9c, 0xfe]);
+    pub const LTUB: Prefix = Prefix([0x01, 0x9d, 0xa4, 0x62]);
+    pub const MTPV: Prefix = Prefix([0x01, 0xb2, 0x67, 0x92]);
+    pub const MTUB: Prefix = Prefix([0x01, 0xb2, 0x6e, 0xf6]);
+    pub const DGPV: Prefix = Prefix([0x02, 0xfa, 0xc3, 0x98]);
+    pub const DGUB: Prefix = Prefix([0x02, 0xfa, 0xca, 0xfd]);
+    /// SLIP-0010 ed25519 keys, which have no base58 serialization.
+    /// Not a SLIP-0132 version, `decode` rejects it.
+    pub const ED25519_PRV: Prefix = Prefix([0x00, 0x00, 0xed, 0x25]);
+    pub const ED25519_PUB: Prefix = Prefix([0x00, 0x01, 0xed, 0x25]);
+
+    /// The version for the script type and network.
+    pub fn of(script_type: ScriptType, network: Network, private: bool) -> Self {
+        VERSIONS
+            .iter()
+            .find(|(_, _, s, n)| *s == script_type && *n == network)
+            .map(|(prv, pbl, _, _)| if private { prv } else { pbl })
+            .cloned()
+            .expect("Every script type must be registered for every network")
+    }
+
+    /// Shortcut to register a single pair.
+    pub fn register(
+        label: impl Into<String>,
+        private: Prefix,
+        public: Prefix,
+    ) -> Result<(), ExtendError> {
+        PrefixRegistry::new()
+            .with(label, private, public)
+            .register()
+    }
 
     pub fn is_public(&self) -> bool {
         !self.is_private()
     }
 
     pub fn is_private(&self) -> bool {
-        PREFIX_PAIRS.contains_key(self)
+        find_pair(|pair| &pair.private == self, self).unwrap_or_default()
     }
 
     pub fn get_public(&self) -> Result<Self, ExtendError> {
-        PREFIX_PAIRS
-            .get(self)
-            .map(|v| v.clone())
-            .ok_or(ExtendError::unsupported_version())
+        find_pair(|pair| pair.clone(), self)
+            .filter(|pair| &pair.private == self)
+            .map(|pair| pair.public)
+            .ok_or(ExtendError::UnknownVersion)
     }
 
     pub fn validate(&self) -> Result<(), ExtendError> {
-        if PREFIX_PAIRS.contains_key(self) {
-            return Ok(());
-        }
-        PREFIX_PAIRS
-            .values()
-            .find(|a| a == &self)
-            .map(|_| ())
-            .ok_or(ExtendError::unsupported_version())
+        find_pair(|_| (), self).ok_or(ExtendError::UnknownVersion)
+    }
+
+    pub fn label(&self) -> Option<String> {
+        find_pair(|pair| pair.label.clone(), self)
+    }
+
+    pub fn script_type(&self) -> Result<ScriptType, ExtendError> {
+        self.find().map(|(_, _, s, _)| *s)
+    }
+
+    pub fn network(&self) -> Result<Network, ExtendError> {
+        self.find().map(|(_, _, _, n)| *n)
+    }
+
+    /// Same network and same visibility with another script type, e.g. `zpub` to `xpub`.
+    pub fn with_script_type(&self, script_type: ScriptType) -> Result<Self, ExtendError> {
+        Ok(Self::of(script_type, self.network()?, self.is_private()))
+    }
+
+    fn find(&self) -> Result<&'static (Prefix, Prefix, ScriptType, Network), ExtendError> {
+        VERSIONS
+            .iter()
+            .find(|(prv, pbl, _, _)| prv == self || pbl == self)
+            .ok_or(ExtendError::UnknownVersion)
     }
 }
 
+/// `chain_code` and `key` are wiped on drop.
+#[derive(Clone)]
 pub struct DecodedExtKey {
     pub prefix: Prefix,
     pub depth: Bytes,
     pub parent: Bytes,
     pub child_number: Bytes,
-    pub chain_code: Bytes,
-    pub key: Bytes,
+    pub chain_code: Zeroizing<Vec<u8>>,
+    pub key: Zeroizing<Vec<u8>>,
 }
 
-pub fn encode(src: &DecodedExtKey) -> Result<String, ExtendError> {
-    let mut buf = BytesMut::with_capacity(ENCODED_BYTE_SIZE);
-    buf.put(src.prefix.as_ref());
-    buf.put(src.depth.as_ref());
-    buf.put(src.parent.as_ref());
-    buf.put(src.child_number.as_ref());
-    buf.put(src.chain_code.as_ref());
-
-    let bs = src.key.as_ref();
+impl fmt::Debug for DecodedExtKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecodedExtKey")
+            .field("prefix", &self.prefix)
+            .field("depth", &self.depth)
+            .field("parent", &self.parent)
+            .field("child_number", &self.child_number)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DecodedExtKey {
+    /// Re-labels the key with the version of another script type. Key data is unchanged.
+    pub fn with_script_type(self, script_type: ScriptType) -> Result<Self, ExtendError> {
+        let prefix = self.prefix.with_script_type(script_type)?;
+        Ok(Self { prefix, ..self })
+    }
+}
+
+pub fn encode_bytes(src: &DecodedExtKey) -> Zeroizing<Vec<u8>> {
+    let mut buf = Zeroizing::new(Vec::with_capacity(ENCODED_BYTE_SIZE));
+    buf.extend_from_slice(src.prefix.as_ref());
+    buf.extend_from_slice(&src.depth);
+    buf.extend_from_slice(&src.parent);
+    buf.extend_from_slice(&src.child_number);
+    buf.extend_from_slice(&src.chain_code);
+
+    let bs = src.key.as_slice();
     if KEY_SIZE == bs.len() {
-        buf.put_u8(0);
+        buf.push(0);
     }
-    buf.put(bs);
+    buf.extend_from_slice(bs);
+    buf
+}
+
+pub fn encode(src: &DecodedExtKey) -> Result<String, ExtendError> {
+    encode_check(&encode_bytes(src))
+}
 
+pub fn decode(src: &str) -> Result<DecodedExtKey, ExtendError> {
+    let mut buf = Zeroizing::new([0; ENCODED_BYTE_SIZE + 4]); // Add length of checksum
+    let len = decode_check(src, buf.as_mut())?;
+    decode_bytes(&buf[..len])
+}
+
+/// Base58Check of up to `ENCODED_BYTE_SIZE` bytes.
+pub(crate) fn encode_check(src: &[u8]) -> Result<String, ExtendError> {
     let mut result = [0u8; MAX_BASE58_SIZE];
-    let len = bs58::encode(&buf)
+    let len = bs58::encode(src)
         .with_check()
         .into(result.as_mut())
-        .map_err(|_| ExtendError::invalid_format("Base58"))?;
+        .map_err(|_| ExtendError::InvalidFormat("Base58"))?;
 
-    String::from_utf8(result[..len].to_vec()).map_err(|_| ExtendError::invalid_format("UTF8"))
+    String::from_utf8(result[..len].to_vec()).map_err(|_| ExtendError::InvalidFormat("UTF8"))
 }
 
-pub fn decode(src: &str) -> Result<DecodedExtKey, ExtendError> {
-    let mut buf = BytesMut::from([0; ENCODED_BYTE_SIZE + 4].as_ref()); // Add length of checksum
-    let len = bs58::decode(src)
-        .with_check(None)
-        .into(buf.as_mut())
-        .map_err(|err| {
-            println!("Error from bs58: {err}");
-            ExtendError::invalid_format("Base58")
-        })?;
+/// Decodes Base58Check into the buffer, which must have room for the checksum.
+pub(crate) fn decode_check(src: &str, buf: &mut [u8]) -> Result<usize, ExtendError> {
+    Ok(bs58::decode(src).with_check(None).into(buf)?)
+}
 
-    if len != ENCODED_BYTE_SIZE {
-        return Err(ExtendError::wrong_length_bytes());
+pub fn decode_bytes(src: &[u8]) -> Result<DecodedExtKey, ExtendError> {
+    if src.len() != ENCODED_BYTE_SIZE {
+        return Err(ExtendError::WrongLength);
     }
-    buf.truncate(len);
-
-    let prefix: Prefix = buf.split_to(4).freeze().try_into()?;
+    let (prefix, rest) = src.split_at(4);
+    let prefix: Prefix = prefix.try_into()?;
     prefix.validate()?;
+    if prefix == Prefix::ED25519_PRV || prefix == Prefix::ED25519_PUB {
+        return Err(ExtendError::UnknownVersion);
+    }
 
-    let depth = buf.split_to(1).freeze();
-    let parent = buf.split_to(4).freeze();
-    let child_number = buf.split_to(4).freeze();
-    let chain_code = buf.split_to(KEY_SIZE).freeze();
+    let (depth, rest) = rest.split_at(1);
+    let (parent, rest) = rest.split_at(4);
+    let (child_number, rest) = rest.split_at(4);
+    let (chain_code, mut key) = rest.split_at(KEY_SIZE);
+
+    if depth == [0] {
+        if parent != [0; 4] {
+            return Err(ExtendError::ZeroDepthWithParent);
+        }
+        if child_number != [0; 4] {
+            return Err(ExtendError::ZeroDepthWithIndex);
+        }
+    }
 
     if prefix.is_private() {
         // Drop first byte
-        let zero = buf.split_to(1).get_u8();
-        if zero != 0 {
-            return Err(ExtendError::invalid_format("extend key"));
+        let head;
+        (head, key) = key.split_at(1);
+        match head {
+            [0] => {}
+            [2 | 3] => return Err(ExtendError::TypeMismatched),
+            _ => return Err(ExtendError::InvalidPrivateKey),
         }
     }
-    let key = buf.freeze();
 
     let reslut = DecodedExtKey {
         prefix,
-        depth,
-        parent,
-        child_number,
-        chain_code,
-        key,
+        depth: Bytes::copy_from_slice(depth),
+        parent: Bytes::copy_from_slice(parent),
+        child_number: Bytes::copy_from_slice(child_number),
+        chain_code: Zeroizing::new(chain_code.to_vec()),
+        key: Zeroizing::new(key.to_vec()),
     };
 
     Ok(reslut)
@@ -165,16 +454,12 @@ mod test {
         assert_eq!(xprv.parent, to_bytes([0; 4]));
         assert_eq!(xprv.child_number, to_bytes([0; 4]));
         assert_eq!(
-            xprv.chain_code,
-            to_bytes(hex!(
-                "873DFF81C02F525623FD1FE5167EAC3A55A049DE3D314BB42EE227FFED37D508"
-            ))
+            xprv.chain_code.as_slice(),
+            hex!("873DFF81C02F525623FD1FE5167EAC3A55A049DE3D314BB42EE227FFED37D508")
         );
         assert_eq!(
-            xprv.key,
-            to_bytes(hex!(
-                "E8F32E723DECF4051AEFAC8E2C93C9C5B214313817CDB01A1494B917C8436B35"
-            ))
+            xprv.key.as_slice(),
+            hex!("E8F32E723DECF4051AEFAC8E2C93C9C5B214313817CDB01A1494B917C8436B35")
         );
         assert_eq!(&xprv.to_string(), xprv_base58);
     }
@@ -190,17 +475,108 @@ mod test {
         assert_eq!(xpub.parent, to_bytes([0; 4]));
         assert_eq!(xpub.child_number, to_bytes([0; 4]));
         assert_eq!(
-            xpub.chain_code,
-            to_bytes(hex!(
-                "873DFF81C02F525623FD1FE5167EAC3A55A049DE3D314BB42EE227FFED37D508"
-            ))
+            xpub.chain_code.as_slice(),
+            hex!("873DFF81C02F525623FD1FE5167EAC3A55A049DE3D314BB42EE227FFED37D508")
         );
         assert_eq!(
-            xpub.key,
-            to_bytes(hex!(
-                "0339A36013301597DAEF41FBE593A02CC513D0B55527EC2DF1050E2E8FF49C85C2"
-            ))
+            xpub.key.as_slice(),
+            hex!("0339A36013301597DAEF41FBE593A02CC513D0B55527EC2DF1050E2E8FF49C85C2")
         );
         assert_eq!(&xpub.to_string(), xpub_base58);
     }
+
+    #[test]
+    fn slip132_versions() {
+        let xpub_base58 = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhe\
+             PY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        [
+            (ScriptType::P2shP2wpkh, "ypub"),
+            (ScriptType::P2wpkh, "zpub"),
+            (ScriptType::P2shP2wsh, "Ypub"),
+            (ScriptType::P2wsh, "Zpub"),
+            (ScriptType::P2pkh, "xpub"),
+        ]
+        .into_iter()
+        .for_each(|(script_type, head)| {
+            let xpub: DecodedExtKey = xpub_base58.parse().unwrap();
+            let key = xpub.key.clone();
+            let converted = xpub.with_script_type(script_type).unwrap().to_string();
+            assert!(converted.starts_with(head), "{converted}");
+
+            let decoded: DecodedExtKey = converted.parse().unwrap();
+            assert_eq!(decoded.prefix.script_type().unwrap(), script_type);
+            assert_eq!(decoded.prefix.network().unwrap(), Network::Mainnet);
+            assert_eq!(decoded.key, key);
+            let back = decoded.with_script_type(ScriptType::P2pkh).unwrap();
+            assert_eq!(back.to_string(), xpub_base58);
+        });
+    }
+
+    #[test]
+    fn slip132_pairs() {
+        assert_eq!(Prefix::ZPRV.get_public().unwrap(), Prefix::ZPUB);
+        assert_eq!(Prefix::VPRV_MULTI.get_public().unwrap(), Prefix::VPUB_MULTI);
+        assert_eq!(
+            Prefix::VPUB.with_script_type(ScriptType::P2pkh).unwrap(),
+            Prefix::TPUB
+        );
+        assert_eq!(
+            Prefix::of(ScriptType::P2shP2wpkh, Network::Testnet, true),
+            Prefix::UPRV
+        );
+        assert!(Prefix::UPUB_MULTI.is_public());
+        assert!(Prefix([0, 0, 0, 0]).validate().is_err());
+    }
+
+    #[test]
+    fn altcoin_prefixes() {
+        let xprv: DecodedExtKey = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPP\
+             qjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi"
+            .parse()
+            .unwrap();
+        [
+            (Prefix::LTPV, "Ltpv"),
+            (Prefix::MTPV, "Mtpv"),
+            (Prefix::DGPV, "dgpv"),
+        ]
+        .into_iter()
+        .for_each(|(prefix, head)| {
+            let key = DecodedExtKey {
+                prefix: prefix.clone(),
+                ..xprv.clone()
+            };
+            let s = key.to_string();
+            assert!(s.starts_with(head), "{s}");
+            let decoded: DecodedExtKey = s.parse().unwrap();
+            assert_eq!(decoded.prefix, prefix);
+            assert!(decoded.prefix.is_private());
+        });
+        assert_eq!(Prefix::LTPV.get_public().unwrap(), Prefix::LTUB);
+        assert_eq!(Prefix::DGUB.label().unwrap(), "Dogecoin dgub");
+    }
+
+    #[test]
+    fn register_prefix() {
+        let private = Prefix([0x0f, 0x00, 0x00, 0x01]);
+        let public = Prefix([0x0f, 0x00, 0x00, 0x02]);
+        assert!(private.validate().is_err());
+
+        Prefix::register("test", private.clone(), public.clone()).unwrap();
+        Prefix::register("test", private.clone(), public.clone()).unwrap();
+        assert!(private.is_private());
+        assert_eq!(private.get_public().unwrap(), public);
+        assert!(public.get_public().is_err());
+        assert!(registered_prefixes().contains(&PrefixPair::new("test", private, public.clone())));
+
+        let conflict = PrefixRegistry::new()
+            .with(
+                "other",
+                Prefix([0x0f, 0x00, 0x00, 0x03]),
+                Prefix([0x0f, 0x00, 0x00, 0x04]),
+            )
+            .with("conflict", Prefix([0x0f, 0x00, 0x00, 0x05]), public)
+            .register();
+        assert!(conflict.is_err());
+        assert!(Prefix([0x0f, 0x00, 0x00, 0x03]).validate().is_err());
+    }
 }
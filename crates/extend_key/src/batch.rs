@@ -0,0 +1,108 @@
+use crate::curve::EcdsaCurve;
+use crate::ecdsa_key::{PubKey, PubKeyBytes, KEY_SIZE};
+use crate::extkey::{ChainCode, ExtKey};
+use crate::ExtendError;
+use core::ops::Range;
+use hdpath::node::Node;
+
+/// Number of children normalized to affine at once.
+const CHUNK_SIZE: usize = 64;
+
+impl<C: EcdsaCurve> ExtKey<PubKeyBytes<C>> {
+    /// `get_child_normal_only` for every index in the range, in order.
+    /// Chunks of the range are derived in parallel with the `parallel` feature.
+    pub fn get_children_normal_only(&self, range: Range<u32>) -> Result<Vec<Self>, ExtendError> {
+        let nodes: Vec<Node> = range.map(Node::from).collect();
+        if nodes.iter().any(|node| node.is_hardened()) {
+            return Err(ExtendError::HardenedFromPublic);
+        }
+        let chunks = map_chunks(&nodes, |chunk| self.derive_chunk(chunk));
+        let mut result = Vec::with_capacity(nodes.len());
+        for chunk in chunks {
+            result.extend(chunk?);
+        }
+        Ok(result)
+    }
+
+    fn derive_chunk(&self, nodes: &[Node]) -> Result<Vec<Self>, ExtendError> {
+        let hashed = nodes
+            .iter()
+            .map(|node| self.hmac_child(self.key.as_ref(), &node.raw_index().into()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let tweaks: Vec<&[u8]> = hashed.iter().map(|h| &h[..KEY_SIZE]).collect();
+        let keys = C::add_point_batch(self.key.as_ref(), &tweaks)?;
+
+        let fingerprint = self.key.fingerprint();
+        let depth = self.depth.increment()?;
+        nodes
+            .iter()
+            .zip(hashed.iter().zip(keys))
+            .map(|(node, (hashed, key))| match key {
+                Some(key) => Ok(ExtKey {
+                    prefix: self.prefix.clone(),
+                    parent: fingerprint.clone(),
+                    chain_code: ChainCode::try_from(&hashed[KEY_SIZE..])?,
+                    key: key.as_ref().try_into()?,
+                    depth: depth.clone(),
+                    child_number: node.raw_index().into(),
+                }),
+                // Invalid child is left to the retry or the error of the sequential path.
+                None => self.get_child_normal_only(*node),
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn map_chunks<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&[T]) -> R + Send + Sync,
+{
+    use rayon::prelude::*;
+    items.par_chunks(CHUNK_SIZE).map(f).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn map_chunks<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    F: Fn(&[T]) -> R,
+{
+    items.chunks(CHUNK_SIZE).map(f).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::base58::Prefix;
+    use crate::curve::NistP256;
+    use crate::ecdsa_key::PrvKeyBytes;
+    use hex_literal::hex;
+
+    fn check<C: EcdsaCurve>() {
+        let m: ExtKey<PrvKeyBytes<C>> = ExtKey::from_seed(
+            Prefix::XPRV,
+            hex!("000102030405060708090a0b0c0d0e0f").as_ref(),
+        )
+        .unwrap();
+        let xpub = m.get_public().unwrap();
+
+        let children = xpub.get_children_normal_only(60..200).unwrap();
+        assert_eq!(children.len(), 140);
+        children.iter().zip(60..).for_each(|(child, i)| {
+            assert_eq!(child, &xpub.get_child_normal_only(Node::Normal(i)).unwrap());
+        });
+
+        assert!(xpub.get_children_normal_only(5..5).unwrap().is_empty());
+        assert!(xpub
+            .get_children_normal_only((1 << 31) - 1..(1 << 31) + 1)
+            .is_err());
+    }
+
+    #[test]
+    fn same_as_sequential() {
+        check::<crate::curve::Secp256k1>();
+        check::<NistP256>();
+    }
+}
@@ -0,0 +1,136 @@
+use crate::ecdsa_key::PrvKey;
+use crate::extkey::ExtKey;
+use crate::ExtendError;
+use hdpath::node::Node;
+use hdpath::path::HDPath;
+use std::collections::HashMap;
+
+struct CachedKey<A: PrvKey> {
+    key: ExtKey<A>,
+    public: A::Public,
+}
+
+impl<A> CachedKey<A>
+where
+    A: PrvKey + Clone,
+{
+    fn new(key: ExtKey<A>) -> Result<Self, ExtendError> {
+        let public = key.key.get_public()?;
+        Ok(Self { key, public })
+    }
+
+    fn child(&self, node: Node) -> Result<Self, ExtendError> {
+        Self::new(self.key.get_child_with_public(node, &self.public)?)
+    }
+}
+
+/// Memoizes the intermediate keys of derived paths with their public keys,
+/// so paths sharing a parent pay only for the last step.
+pub struct DerivationCache<A: PrvKey> {
+    root: CachedKey<A>,
+    nodes: HashMap<Vec<Node>, CachedKey<A>>,
+}
+
+impl<A> DerivationCache<A>
+where
+    A: PrvKey + Clone,
+    A::Public: Clone,
+{
+    pub fn new(root: ExtKey<A>) -> Result<Self, ExtendError> {
+        Ok(Self {
+            root: CachedKey::new(root)?,
+            nodes: HashMap::new(),
+        })
+    }
+
+    pub fn root(&self) -> &ExtKey<A> {
+        &self.root.key
+    }
+
+    /// Same as `ExtKey::derive_child` from the root.
+    /// Every ancestor of the target is cached, but not the target itself.
+    pub fn derive(&mut self, path: &HDPath) -> Result<ExtKey<A>, ExtendError> {
+        let (last, parents) = path
+            .nodes()
+            .split_last()
+            .ok_or(ExtendError::InvalidHDPath(None))?;
+        let parent = self.parent(parents)?;
+        parent.key.get_child_with_public(*last, &parent.public)
+    }
+
+    pub fn derive_public(&mut self, path: &HDPath) -> Result<ExtKey<A::Public>, ExtendError> {
+        self.derive(path)?.get_public()
+    }
+
+    /// Number of the cached keys, excluding the root.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
+
+    fn parent(&mut self, nodes: &[Node]) -> Result<&CachedKey<A>, ExtendError> {
+        let cached = (0..=nodes.len())
+            .rev()
+            .find(|&i| i == 0 || self.nodes.contains_key(&nodes[..i]))
+            .unwrap_or_default();
+        for i in cached..nodes.len() {
+            let parent = self.get(&nodes[..i]);
+            let child = parent.child(nodes[i])?;
+            self.nodes.insert(nodes[..=i].to_vec(), child);
+        }
+        Ok(self.get(nodes))
+    }
+
+    fn get(&self, nodes: &[Node]) -> &CachedKey<A> {
+        if nodes.is_empty() {
+            &self.root
+        } else {
+            &self.nodes[nodes]
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::base58::Prefix;
+    use crate::ecdsa_key::PrvKeyBytes;
+    use hex_literal::hex;
+
+    #[test]
+    fn same_as_derive_child() {
+        let m: ExtKey<PrvKeyBytes> = ExtKey::from_seed(
+            Prefix::XPRV,
+            hex!("000102030405060708090a0b0c0d0e0f").as_ref(),
+        )
+        .unwrap();
+        let mut cache = DerivationCache::new(m.clone()).unwrap();
+
+        (0..5).for_each(|i| {
+            let path: HDPath = format!("m/44'/60'/0'/0/{i}").parse().unwrap();
+            assert_eq!(
+                cache.derive(&path).unwrap(),
+                m.derive_child(path.clone()).unwrap()
+            );
+            assert_eq!(
+                cache.derive_public(&path).unwrap(),
+                m.derive_child(path).unwrap().get_public().unwrap()
+            );
+        });
+        assert_eq!(cache.len(), 4);
+
+        let path: HDPath = "m/44'/60'/1'".parse().unwrap();
+        assert_eq!(cache.derive(&path).unwrap(), m.derive_child(path).unwrap());
+        assert_eq!(cache.len(), 4);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,287 @@
+use crate::ecdsa_key::KEY_SIZE;
+use crate::ExtendError;
+use core::fmt::Debug;
+use core::hash::Hash;
+
+pub type CompressedBytes = [u8; KEY_SIZE + 1];
+pub type UncompressedBytes = [u8; KEY_SIZE * 2 + 1];
+/// `r || s`
+pub type CompactSignature = [u8; KEY_SIZE * 2];
+
+/// Short Weierstrass curve used by `PrvKeyBytes` and `PubKeyBytes`.
+/// Keys are handled as big-endian scalars and SEC1 compressed points.
+pub trait EcdsaCurve: Debug + Clone + PartialEq + Eq + Hash + Send + Sync {
+    /// HMAC key to derive the master key from a seed.
+    const SEED_KEY: &'static [u8];
+
+    /// True if an invalid derived key is retried as SLIP-0010 says,
+    /// otherwise the derivation fails as BIP-0032 says.
+    const RETRY_INVALID: bool;
+
+    /// True if the bytes is a scalar in `[1, n - 1]`.
+    fn is_valid_scalar(key: &[u8]) -> bool;
+
+    fn is_valid_point(key: &[u8]) -> bool;
+
+    /// `key + tweak (mod n)`, `None` if the tweak is not below n or the result is zero.
+    fn add_scalar(key: &[u8], tweak: &[u8]) -> Result<Option<[u8; KEY_SIZE]>, ExtendError>;
+
+    /// `key - tweak (mod n)`, `None` if the tweak is not below n or the result is zero.
+    fn sub_scalar(key: &[u8], tweak: &[u8]) -> Result<Option<[u8; KEY_SIZE]>, ExtendError>;
+
+    /// `point + tweak * G`, `None` if the tweak is not below n or the result is infinity.
+    fn add_point(point: &[u8], tweak: &[u8]) -> Result<Option<CompressedBytes>, ExtendError>;
+
+    /// `add_point` for many tweaks, normalizing the results to affine at once.
+    fn add_point_batch(
+        point: &[u8],
+        tweaks: &[&[u8]],
+    ) -> Result<Vec<Option<CompressedBytes>>, ExtendError>;
+
+    fn public_key(key: &[u8]) -> Result<CompressedBytes, ExtendError>;
+
+    fn uncompress(point: &[u8]) -> Result<UncompressedBytes, ExtendError>;
+
+    /// `key * point`
+    fn multiply(point: &[u8], key: &[u8]) -> Result<CompressedBytes, ExtendError>;
+
+    /// Deterministic ECDSA (RFC 6979), normalized to low-S.
+    /// Returns the signature and the recovery id.
+    fn sign_prehash(key: &[u8], digest: &[u8]) -> Result<(CompactSignature, u8), ExtendError>;
+
+    /// Accepts high-S signatures on every curve.
+    fn verify_prehash(point: &[u8], digest: &[u8], sig: &[u8]) -> bool;
+
+    fn recover_prehash(
+        digest: &[u8],
+        sig: &[u8],
+        recovery_id: u8,
+    ) -> Result<CompressedBytes, ExtendError>;
+
+    /// `None` if s is already low.
+    fn normalize_s(sig: &[u8]) -> Result<Option<CompactSignature>, ExtendError>;
+
+    fn to_der(sig: &[u8]) -> Result<Vec<u8>, ExtendError>;
+
+    fn from_der(der: &[u8]) -> Result<CompactSignature, ExtendError>;
+}
+
+macro_rules! ecdsa_curve {
+    ($t:ident, $c:ident, $seed:expr, $retry:expr) => {
+        impl EcdsaCurve for $t {
+            const SEED_KEY: &'static [u8] = $seed;
+            const RETRY_INVALID: bool = $retry;
+
+            fn is_valid_scalar(key: &[u8]) -> bool {
+                to_scalar(key)
+                    .map(|a| !bool::from(Field::is_zero(&a)))
+                    .unwrap_or_default()
+            }
+
+            fn is_valid_point(key: &[u8]) -> bool {
+                to_point(key).is_ok()
+            }
+
+            fn add_scalar(key: &[u8], tweak: &[u8]) -> Result<Option<[u8; KEY_SIZE]>, ExtendError> {
+                let a = to_scalar(key).ok_or(ExtendError::InvalidPrivateKey)?;
+                let c = to_scalar(tweak)
+                    .map(|b| a + b)
+                    .filter(|c| !bool::from(Field::is_zero(c)))
+                    .map(|c| c.to_repr().into());
+                Ok(c)
+            }
+
+            fn sub_scalar(key: &[u8], tweak: &[u8]) -> Result<Option<[u8; KEY_SIZE]>, ExtendError> {
+                let a = to_scalar(key).ok_or(ExtendError::InvalidPrivateKey)?;
+                let c = to_scalar(tweak)
+                    .map(|b| a - b)
+                    .filter(|c| !bool::from(Field::is_zero(c)))
+                    .map(|c| c.to_repr().into());
+                Ok(c)
+            }
+
+            fn add_point(
+                point: &[u8],
+                tweak: &[u8],
+            ) -> Result<Option<CompressedBytes>, ExtendError> {
+                let a = to_point(point)?;
+                let c = to_scalar(tweak)
+                    .map(|b| $c::ProjectivePoint::GENERATOR * b + a)
+                    .filter(|c| !bool::from(c.is_identity()))
+                    .map(|c| compress(&c.to_affine()))
+                    .transpose()?;
+                Ok(c)
+            }
+
+            fn add_point_batch(
+                point: &[u8],
+                tweaks: &[&[u8]],
+            ) -> Result<Vec<Option<CompressedBytes>>, ExtendError> {
+                let a = $c::ProjectivePoint::from(to_point(point)?);
+                let sums: Vec<Option<$c::ProjectivePoint>> = tweaks
+                    .iter()
+                    .map(|tweak| {
+                        to_scalar(tweak)
+                            .map(|b| $c::ProjectivePoint::GENERATOR * b + a)
+                            .filter(|c| !bool::from(c.is_identity()))
+                    })
+                    .collect();
+                let projective: Vec<_> = sums.iter().flatten().copied().collect();
+                let mut affine = vec![$c::AffinePoint::default(); projective.len()];
+                Curve::batch_normalize(&projective, &mut affine);
+
+                let mut affine = affine.iter();
+                sums.iter()
+                    .map(|sum| sum.and(affine.next()).map(compress).transpose())
+                    .collect()
+            }
+
+            fn public_key(key: &[u8]) -> Result<CompressedBytes, ExtendError> {
+                let a = $c::NonZeroScalar::try_from(key)?;
+                let b = $c::ProjectivePoint::GENERATOR * *a;
+                compress(&b.to_affine())
+            }
+
+            fn uncompress(point: &[u8]) -> Result<UncompressedBytes, ExtendError> {
+                let encoded = to_point(point)?.to_encoded_point(false);
+                encoded
+                    .as_bytes()
+                    .try_into()
+                    .map_err(|_| ExtendError::WrongLength)
+            }
+
+            fn multiply(point: &[u8], key: &[u8]) -> Result<CompressedBytes, ExtendError> {
+                let a = $c::NonZeroScalar::try_from(key)?;
+                let b = $c::ProjectivePoint::from(to_point(point)?) * *a;
+                compress(&b.to_affine())
+            }
+
+            fn sign_prehash(
+                key: &[u8],
+                digest: &[u8],
+            ) -> Result<(CompactSignature, u8), ExtendError> {
+                let key =
+                    SigningKey::from_slice(key).map_err(|_| ExtendError::InvalidPrivateKey)?;
+                let (sig, recovery_id) = key
+                    .sign_prehash_recoverable(digest)
+                    .map_err(|_| ExtendError::InvalidFormat("digest"))?;
+                let (sig, recovery_id) = match sig.normalize_s() {
+                    Some(low) => (
+                        low,
+                        RecoveryId::new(!recovery_id.is_y_odd(), recovery_id.is_x_reduced()),
+                    ),
+                    None => (sig, recovery_id),
+                };
+                Ok((to_compact(&sig)?, recovery_id.to_byte()))
+            }
+
+            fn verify_prehash(point: &[u8], digest: &[u8], sig: &[u8]) -> bool {
+                let verify = || -> Option<()> {
+                    let key = VerifyingKey::from_sec1_bytes(point).ok()?;
+                    let sig = from_compact(sig).ok()?;
+                    let sig = sig.normalize_s().unwrap_or(sig);
+                    key.verify_prehash(digest, &sig).ok()
+                };
+                verify().is_some()
+            }
+
+            fn recover_prehash(
+                digest: &[u8],
+                sig: &[u8],
+                recovery_id: u8,
+            ) -> Result<CompressedBytes, ExtendError> {
+                let recovery_id = RecoveryId::from_byte(recovery_id)
+                    .ok_or(ExtendError::InvalidFormat("recovery id"))?;
+                let key =
+                    VerifyingKey::recover_from_prehash(digest, &from_compact(sig)?, recovery_id)
+                        .map_err(|_| ExtendError::InvalidSignature)?;
+                key.to_encoded_point(true)
+                    .as_bytes()
+                    .try_into()
+                    .map_err(|_| ExtendError::WrongLength)
+            }
+
+            fn normalize_s(sig: &[u8]) -> Result<Option<CompactSignature>, ExtendError> {
+                from_compact(sig)?
+                    .normalize_s()
+                    .map(|s| to_compact(&s))
+                    .transpose()
+            }
+
+            fn to_der(sig: &[u8]) -> Result<Vec<u8>, ExtendError> {
+                Ok(from_compact(sig)?.to_der().as_bytes().to_vec())
+            }
+
+            fn from_der(der: &[u8]) -> Result<CompactSignature, ExtendError> {
+                let sig = Signature::from_der(der)
+                    .map_err(|_| ExtendError::InvalidFormat("DER signature"))?;
+                to_compact(&sig)
+            }
+        }
+
+        fn from_compact(sig: &[u8]) -> Result<Signature, ExtendError> {
+            Signature::from_slice(sig).map_err(|_| ExtendError::InvalidFormat("signature"))
+        }
+
+        fn to_compact(sig: &Signature) -> Result<CompactSignature, ExtendError> {
+            sig.to_bytes()
+                .as_slice()
+                .try_into()
+                .map_err(|_| ExtendError::WrongLength)
+        }
+
+        fn to_scalar(bs: &[u8]) -> Option<$c::Scalar> {
+            if bs.len() != KEY_SIZE {
+                return None;
+            }
+            $c::Scalar::from_repr(*$c::FieldBytes::from_slice(bs)).into()
+        }
+
+        fn to_point(bs: &[u8]) -> Result<$c::AffinePoint, ExtendError> {
+            let encoded =
+                $c::EncodedPoint::from_bytes(bs).map_err(|_| ExtendError::InvalidPublicKey)?;
+            let o: Option<_> = $c::AffinePoint::from_encoded_point(&encoded).into();
+            o.ok_or(ExtendError::InvalidPublicKey)
+        }
+
+        fn compress(point: &$c::AffinePoint) -> Result<CompressedBytes, ExtendError> {
+            point
+                .to_encoded_point(true)
+                .as_bytes()
+                .try_into()
+                .map_err(|_| ExtendError::WrongLength)
+        }
+    };
+}
+
+//----------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Secp256k1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NistP256;
+
+mod secp256k1 {
+    use super::*;
+    use ecdsa::RecoveryId;
+    use elliptic_curve::ff::{Field, PrimeField};
+    use elliptic_curve::group::{Curve, Group};
+    use elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+    use k256::ecdsa::signature::hazmat::PrehashVerifier;
+    use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+
+    ecdsa_curve!(Secp256k1, k256, b"Bitcoin seed", false);
+}
+
+mod nist_p256 {
+    use super::*;
+    use ecdsa::RecoveryId;
+    use elliptic_curve::ff::{Field, PrimeField};
+    use elliptic_curve::group::{Curve, Group};
+    use elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+    use p256::ecdsa::signature::hazmat::PrehashVerifier;
+    use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+
+    ecdsa_curve!(NistP256, p256, b"Nist256p1 seed", true);
+}
@@ -0,0 +1,599 @@
+use crate::any_key::AnyExtKey;
+use crate::base58::{self, Network};
+use crate::ecdsa_key::{KeyBytes, PrvKey, PubKeyBytes};
+use crate::hex;
+use crate::origin::KeyOrigin;
+use crate::schnorr::XOnlyPubKey;
+use crate::ExtendError;
+use bech32::{ToBase32, Variant};
+use core::fmt;
+use core::ops::Range;
+use core::str::FromStr;
+use hdpath::node::Node;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+const INPUT_CHARSET: &str = concat!(
+    "0123456789()[],'/*abcdefgh@:$%{}",
+    "IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~",
+    "ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ",
+);
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const CHECKSUM_SIZE: usize = 8;
+
+const OP_0: u8 = 0x00;
+const OP_1: u8 = 0x51;
+const OP_DUP: u8 = 0x76;
+const OP_EQUAL: u8 = 0x87;
+const OP_EQUALVERIFY: u8 = 0x88;
+const OP_HASH160: u8 = 0xa9;
+const OP_CHECKSIG: u8 = 0xac;
+const OP_CHECKMULTISIG: u8 = 0xae;
+
+/// Max number of keys in bare `multi()`, as Bitcoin Core relays as standard.
+const MAX_MULTI_KEYS_BARE: usize = 3;
+/// Max number of keys in `multi()` of P2SH, as the script must be in 520 bytes.
+const MAX_MULTI_KEYS_SH: usize = 15;
+/// Max number of keys of `OP_CHECKMULTISIG`.
+const MAX_MULTI_KEYS: usize = 20;
+
+fn invalid() -> ExtendError {
+    ExtendError::InvalidFormat("descriptor")
+}
+
+//----------------------------------------------------------------
+
+fn polymod(c: u64, value: u64) -> u64 {
+    const GENERATOR: [u64; 5] = [
+        0xf5dee51989,
+        0xa9fdca3312,
+        0x1bab10e32d,
+        0x3706b1677a,
+        0x644d626ffd,
+    ];
+    let top = c >> 35;
+    let c = ((c & 0x7ffffffff) << 5) ^ value;
+    GENERATOR
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| (top >> i) & 1 == 1)
+        .fold(c, |c, (_, g)| c ^ g)
+}
+
+/// BIP-0380 checksum of the descriptor without `#`.
+pub fn checksum(desc: &str) -> Result<String, ExtendError> {
+    let mut c = 1;
+    let mut groups = Vec::with_capacity(3);
+    for ch in desc.chars() {
+        let v = INPUT_CHARSET.find(ch).ok_or_else(invalid)? as u64;
+        c = polymod(c, v & 31);
+        groups.push(v >> 5);
+        if groups.len() == 3 {
+            c = polymod(c, groups[0] * 9 + groups[1] * 3 + groups[2]);
+            groups.clear();
+        }
+    }
+    match groups[..] {
+        [a] => c = polymod(c, a),
+        [a, b] => c = polymod(c, a * 3 + b),
+        _ => {}
+    }
+    let c = (0..CHECKSUM_SIZE).fold(c, |c, _| polymod(c, 0)) ^ 1;
+    let s = (0..CHECKSUM_SIZE)
+        .map(|i| CHECKSUM_CHARSET[((c >> (5 * (7 - i))) & 31) as usize] as char)
+        .collect();
+    Ok(s)
+}
+
+//----------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Wildcard {
+    None,
+    Normal,
+    Hardened,
+}
+
+/// Key expression of descriptors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DescriptorKey {
+    /// Hex encoded compressed public key.
+    Single {
+        origin: Option<KeyOrigin>,
+        key: PubKeyBytes,
+    },
+    /// Hex encoded x-only public key, only in `tr()`.
+    XOnly {
+        origin: Option<KeyOrigin>,
+        key: XOnlyPubKey,
+    },
+    /// Extended key followed by the path and the wildcard.
+    Extended {
+        origin: Option<KeyOrigin>,
+        key: AnyExtKey,
+        path: Vec<Node>,
+        wildcard: Wildcard,
+    },
+}
+
+impl DescriptorKey {
+    pub fn origin(&self) -> Option<&KeyOrigin> {
+        match self {
+            Self::Single { origin, .. } => origin.as_ref(),
+            Self::XOnly { origin, .. } => origin.as_ref(),
+            Self::Extended { origin, .. } => origin.as_ref(),
+        }
+    }
+
+    pub fn is_ranged(&self) -> bool {
+        matches!(self, Self::Extended { wildcard, .. } if *wildcard != Wildcard::None)
+    }
+
+    /// The public key at `index`, which is ignored if the key is not ranged.
+    pub fn public_key(&self, index: u32) -> Result<PubKeyBytes, ExtendError> {
+        let (key, path, wildcard) = match self {
+            Self::Single { key, .. } => return Ok(key.clone()),
+            Self::XOnly { .. } => return Err(ExtendError::TypeMismatched),
+            Self::Extended {
+                key,
+                path,
+                wildcard,
+                ..
+            } => (key, path, wildcard),
+        };
+        let last = match wildcard {
+            Wildcard::None => None,
+            Wildcard::Normal => Some(Node::Normal(index)),
+            Wildcard::Hardened => Some(Node::Hardened(index)),
+        };
+        let mut nodes = path.iter().chain(&last);
+        match key {
+            AnyExtKey::Private(k) => {
+                let child = nodes.try_fold(k.clone(), |parent, node| parent.get_child(*node))?;
+                child.key.get_public()
+            }
+            AnyExtKey::Public(k) => {
+                let child = nodes.try_fold(k.clone(), |parent, node| {
+                    parent.get_child_normal_only(*node)
+                })?;
+                Ok(child.key)
+            }
+        }
+    }
+
+    pub fn x_only(&self, index: u32) -> Result<XOnlyPubKey, ExtendError> {
+        match self {
+            Self::XOnly { key, .. } => Ok(key.clone()),
+            _ => Ok(self.public_key(index)?.x_only()),
+        }
+    }
+
+    fn parse(s: &str, x_only: bool) -> Result<Self, ExtendError> {
+        let (origin, s) = match s.strip_prefix('[') {
+            Some(rest) => {
+                let (origin, s) = rest.split_once(']').ok_or_else(invalid)?;
+                (Some(format!("[{}]", hardened_marks(origin)).parse()?), s)
+            }
+            None => (None, s),
+        };
+        let is_hex = s.chars().all(|c| c.is_ascii_hexdigit());
+        if is_hex && s.len() == 66 {
+            let key: PubKeyBytes = hex::decode(s, "public key")?.as_slice().try_into()?;
+            key.validate()?;
+            return Ok(Self::Single { origin, key });
+        }
+        if is_hex && s.len() == 64 && x_only {
+            let key: XOnlyPubKey = hex::decode(s, "x-only public key")?.as_slice().try_into()?;
+            key.validate()?;
+            return Ok(Self::XOnly { origin, key });
+        }
+
+        let mut parts = s.split('/');
+        let key: AnyExtKey = parts.next().ok_or_else(invalid)?.parse()?;
+        let mut path = Vec::new();
+        let mut wildcard = Wildcard::None;
+        for part in parts {
+            if wildcard != Wildcard::None {
+                return Err(invalid());
+            }
+            match part {
+                "*" => wildcard = Wildcard::Normal,
+                "*'" | "*h" => wildcard = Wildcard::Hardened,
+                _ => path.push(hardened_marks(part).parse().map_err(|_| invalid())?),
+            }
+        }
+        Ok(Self::Extended {
+            origin,
+            key,
+            path,
+            wildcard,
+        })
+    }
+}
+
+/// `h` is accepted as well as `'` for hardened indices.
+fn hardened_marks(s: &str) -> String {
+    s.replace('h', "'")
+}
+
+impl fmt::Display for DescriptorKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(origin) = self.origin() {
+            origin.fmt(f)?;
+        }
+        match self {
+            Self::Single { key, .. } => f.write_str(&hex::encode(key.as_ref())),
+            Self::XOnly { key, .. } => f.write_str(&hex::encode(key.as_ref())),
+            Self::Extended {
+                key,
+                path,
+                wildcard,
+                ..
+            } => {
+                key.fmt(f)?;
+                path.iter().try_for_each(|node| write!(f, "/{node}"))?;
+                match wildcard {
+                    Wildcard::None => Ok(()),
+                    Wildcard::Normal => f.write_str("/*"),
+                    Wildcard::Hardened => f.write_str("/*'"),
+                }
+            }
+        }
+    }
+}
+
+//----------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Multi {
+    pub threshold: usize,
+    pub keys: Vec<DescriptorKey>,
+    /// True for `sortedmulti()`.
+    pub sorted: bool,
+}
+
+impl Multi {
+    fn script(&self, index: u32) -> Result<Vec<u8>, ExtendError> {
+        let mut keys = self
+            .keys
+            .iter()
+            .map(|k| k.public_key(index))
+            .collect::<Result<Vec<_>, _>>()?;
+        if self.sorted {
+            keys.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        }
+        let mut script = vec![];
+        push_int(&mut script, self.threshold)?;
+        keys.iter().for_each(|k| push_data(&mut script, k.as_ref()));
+        push_int(&mut script, keys.len())?;
+        script.push(OP_CHECKMULTISIG);
+        Ok(script)
+    }
+
+    fn parse(args: &str, sorted: bool, max_keys: usize) -> Result<Self, ExtendError> {
+        let mut args = args.split(',');
+        let threshold: usize = args
+            .next()
+            .and_then(|k| k.parse().ok())
+            .ok_or_else(invalid)?;
+        let keys = args
+            .map(|k| DescriptorKey::parse(k, false))
+            .collect::<Result<Vec<_>, _>>()?;
+        if threshold == 0 || keys.len() < threshold || keys.len() > max_keys {
+            return Err(invalid());
+        }
+        Ok(Self {
+            threshold,
+            keys,
+            sorted,
+        })
+    }
+}
+
+impl fmt::Display for Multi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = if self.sorted { "sortedmulti" } else { "multi" };
+        write!(f, "{name}({}", self.threshold)?;
+        self.keys.iter().try_for_each(|k| write!(f, ",{k}"))?;
+        f.write_str(")")
+    }
+}
+
+/// `OP_1` to `OP_16`, or a one byte push up to `MAX_MULTI_KEYS`.
+fn push_int(script: &mut Vec<u8>, n: usize) -> Result<(), ExtendError> {
+    match n {
+        1..=16 => script.push(OP_1 + n as u8 - 1),
+        17..=MAX_MULTI_KEYS => push_data(script, &[n as u8]),
+        _ => return Err(invalid()),
+    }
+    Ok(())
+}
+
+/// Only for data shorter than `OP_PUSHDATA1`.
+fn push_data(script: &mut Vec<u8>, data: &[u8]) {
+    script.push(data.len() as u8);
+    script.extend_from_slice(data);
+}
+
+fn hash160(data: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(Sha256::digest(data)).into()
+}
+
+//----------------------------------------------------------------
+
+/// Output descriptor of BIP-0380 to BIP-0386, without script trees of `tr()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Descriptor {
+    Pkh(DescriptorKey),
+    Wpkh(DescriptorKey),
+    /// `sh()` of `pkh()`, `wpkh()`, `wsh()` or `multi()`.
+    Sh(Box<Descriptor>),
+    /// `wsh()` of `pkh()` or `multi()`.
+    Wsh(Box<Descriptor>),
+    Multi(Multi),
+    Tr(DescriptorKey),
+}
+
+impl Descriptor {
+    pub fn is_ranged(&self) -> bool {
+        match self {
+            Self::Pkh(k) | Self::Wpkh(k) | Self::Tr(k) => k.is_ranged(),
+            Self::Sh(d) | Self::Wsh(d) => d.is_ranged(),
+            Self::Multi(m) => m.keys.iter().any(DescriptorKey::is_ranged),
+        }
+    }
+
+    /// The scriptPubKey at `index`, which is ignored if the descriptor is not ranged.
+    pub fn script_pubkey(&self, index: u32) -> Result<Vec<u8>, ExtendError> {
+        let script = match self {
+            Self::Pkh(k) => {
+                let mut s = vec![OP_DUP, OP_HASH160];
+                push_data(&mut s, &hash160(k.public_key(index)?.as_ref()));
+                s.extend([OP_EQUALVERIFY, OP_CHECKSIG]);
+                s
+            }
+            Self::Wpkh(k) => {
+                let mut s = vec![OP_0];
+                push_data(&mut s, &hash160(k.public_key(index)?.as_ref()));
+                s
+            }
+            Self::Sh(d) => {
+                let mut s = vec![OP_HASH160];
+                push_data(&mut s, &hash160(&d.redeem_script(index)?));
+                s.push(OP_EQUAL);
+                s
+            }
+            Self::Wsh(d) => {
+                let mut s = vec![OP_0];
+                push_data(&mut s, &Sha256::digest(d.redeem_script(index)?));
+                s
+            }
+            Self::Multi(m) => m.script(index)?,
+            Self::Tr(k) => {
+                let (output, _) = k.x_only(index)?.tap_tweak(None)?;
+                let mut s = vec![OP_1];
+                push_data(&mut s, output.as_ref());
+                s
+            }
+        };
+        Ok(script)
+    }
+
+    /// Script which is hashed into the outer `sh()` or `wsh()`.
+    fn redeem_script(&self, index: u32) -> Result<Vec<u8>, ExtendError> {
+        match self {
+            Self::Multi(m) => m.script(index),
+            _ => self.script_pubkey(index),
+        }
+    }
+
+    pub fn address(&self, index: u32, network: Network) -> Result<String, ExtendError> {
+        address(&self.script_pubkey(index)?, network)
+    }
+
+    pub fn script_pubkeys(&self, range: Range<u32>) -> Result<Vec<Vec<u8>>, ExtendError> {
+        range.map(|i| self.script_pubkey(i)).collect()
+    }
+
+    pub fn addresses(
+        &self,
+        range: Range<u32>,
+        network: Network,
+    ) -> Result<Vec<String>, ExtendError> {
+        range.map(|i| self.address(i, network)).collect()
+    }
+
+    /// String form followed by `#` and the checksum.
+    pub fn to_string_with_checksum(&self) -> Result<String, ExtendError> {
+        let s = self.to_string();
+        Ok(format!("{s}#{}", checksum(&s)?))
+    }
+
+    fn parse(s: &str, ctx: Context) -> Result<Self, ExtendError> {
+        let (name, args) = s
+            .strip_suffix(')')
+            .and_then(|s| s.split_once('('))
+            .ok_or_else(invalid)?;
+        let max_keys = match ctx {
+            Context::Top => MAX_MULTI_KEYS_BARE,
+            Context::Sh => MAX_MULTI_KEYS_SH,
+            Context::Wsh => MAX_MULTI_KEYS,
+        };
+        let d = match name {
+            "pkh" => Self::Pkh(DescriptorKey::parse(args, false)?),
+            "wpkh" if ctx != Context::Wsh => Self::Wpkh(DescriptorKey::parse(args, false)?),
+            "multi" => Self::Multi(Multi::parse(args, false, max_keys)?),
+            "sortedmulti" => Self::Multi(Multi::parse(args, true, max_keys)?),
+            "sh" if ctx == Context::Top => Self::Sh(Box::new(Self::parse(args, Context::Sh)?)),
+            "wsh" if ctx != Context::Wsh => Self::Wsh(Box::new(Self::parse(args, Context::Wsh)?)),
+            "tr" if ctx == Context::Top => Self::Tr(DescriptorKey::parse(args, true)?),
+            _ => return Err(invalid()),
+        };
+        Ok(d)
+    }
+}
+
+/// Where a descriptor is nested, which limits what it can contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+    Top,
+    Sh,
+    Wsh,
+}
+
+impl fmt::Display for Descriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pkh(k) => write!(f, "pkh({k})"),
+            Self::Wpkh(k) => write!(f, "wpkh({k})"),
+            Self::Sh(d) => write!(f, "sh({d})"),
+            Self::Wsh(d) => write!(f, "wsh({d})"),
+            Self::Multi(m) => m.fmt(f),
+            Self::Tr(k) => write!(f, "tr({k})"),
+        }
+    }
+}
+
+/// The checksum is verified if it is given after `#`.
+impl FromStr for Descriptor {
+    type Err = ExtendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let desc = match s.split_once('#') {
+            Some((desc, sum)) => {
+                if checksum(desc)? != sum {
+                    return Err(ExtendError::InvalidChecksum);
+                }
+                desc
+            }
+            None => s,
+        };
+        Self::parse(desc, Context::Top)
+    }
+}
+
+//----------------------------------------------------------------
+
+/// Address of the standard scriptPubKey.
+pub fn address(script: &[u8], network: Network) -> Result<String, ExtendError> {
+    let (p2pkh, p2sh, hrp) = match network {
+        Network::Mainnet => (0x00, 0x05, "bc"),
+        Network::Testnet => (0x6f, 0xc4, "tb"),
+    };
+    let legacy = |version: u8, hash: &[u8]| base58::encode_check(&[&[version], hash].concat());
+    let segwit = |version: u8, program: &[u8], variant: Variant| {
+        let mut data = vec![bech32::u5::try_from_u8(version).map_err(|_| invalid())?];
+        data.extend(program.to_base32());
+        bech32::encode(hrp, data, variant).map_err(|_| ExtendError::InvalidFormat("bech32"))
+    };
+    match script {
+        [OP_DUP, OP_HASH160, 20, hash @ .., OP_EQUALVERIFY, OP_CHECKSIG] if hash.len() == 20 => {
+            legacy(p2pkh, hash)
+        }
+        [OP_HASH160, 20, hash @ .., OP_EQUAL] if hash.len() == 20 => legacy(p2sh, hash),
+        [OP_0, 20, program @ ..] if program.len() == 20 => segwit(0, program, Variant::Bech32),
+        [OP_0, 32, program @ ..] if program.len() == 32 => segwit(0, program, Variant::Bech32),
+        [OP_1, 32, program @ ..] if program.len() == 32 => segwit(1, program, Variant::Bech32m),
+        _ => Err(ExtendError::NoAddress),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn checksum_bip380() {
+        assert_eq!(checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+        assert!(checksum("raw(dead\u{e9}beef)").is_err());
+    }
+
+    #[test]
+    fn single_keys() {
+        let g = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let check = |desc: String, script: &[u8], address: &str| {
+            let d: Descriptor = desc.parse().unwrap();
+            assert_eq!(d.to_string(), desc);
+            assert!(!d.is_ranged());
+            assert_eq!(d.script_pubkey(0).unwrap(), script);
+            assert_eq!(d.address(0, Network::Mainnet).unwrap(), address);
+            let with_checksum = d.to_string_with_checksum().unwrap();
+            assert_eq!(with_checksum.parse::<Descriptor>().unwrap(), d);
+        };
+        check(
+            format!("pkh({g})"),
+            &hex!("76a914751e76e8199196d454941c45d1b3a323f1433bd688ac"),
+            "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH",
+        );
+        check(
+            format!("wpkh({g})"),
+            &hex!("0014751e76e8199196d454941c45d1b3a323f1433bd6"),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        );
+
+        let pkh: Descriptor = format!("pkh({g})").parse().unwrap();
+        let mut p2sh = vec![OP_HASH160, 20];
+        p2sh.extend(hash160(&pkh.script_pubkey(0).unwrap()));
+        p2sh.push(OP_EQUAL);
+        let sh: Descriptor = format!("sh(pkh({g}))").parse().unwrap();
+        assert_eq!(sh.script_pubkey(0).unwrap(), p2sh);
+        let mut p2wsh = vec![OP_0, 32];
+        p2wsh.extend(Sha256::digest(pkh.script_pubkey(0).unwrap()));
+        let wsh: Descriptor = format!("wsh(pkh({g}))").parse().unwrap();
+        assert_eq!(wsh.script_pubkey(0).unwrap(), p2wsh);
+        let mut p2sh_p2wsh = vec![OP_HASH160, 20];
+        p2sh_p2wsh.extend(hash160(&p2wsh));
+        p2sh_p2wsh.push(OP_EQUAL);
+        let sh_wsh: Descriptor = format!("sh(wsh(pkh({g})))").parse().unwrap();
+        assert_eq!(sh_wsh.script_pubkey(0).unwrap(), p2sh_p2wsh);
+
+        let bare: Descriptor = format!("multi(1,{g})").parse().unwrap();
+        assert!(matches!(
+            bare.address(0, Network::Mainnet),
+            Err(ExtendError::NoAddress)
+        ));
+    }
+
+    #[test]
+    fn multi_key_limits() {
+        let g = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let multi = |n: usize| format!("multi(1{})", format!(",{g}").repeat(n));
+        assert!(multi(3).parse::<Descriptor>().is_ok());
+        assert!(multi(4).parse::<Descriptor>().is_err());
+        assert!(format!("sh({})", multi(15)).parse::<Descriptor>().is_ok());
+        assert!(format!("sh({})", multi(16)).parse::<Descriptor>().is_err());
+        assert!(format!("sh(wsh({}))", multi(20))
+            .parse::<Descriptor>()
+            .is_ok());
+        assert!(format!("wsh({})", multi(21)).parse::<Descriptor>().is_err());
+
+        let wsh: Descriptor = format!("wsh({})", multi(20)).parse().unwrap();
+        let Descriptor::Wsh(inner) = &wsh else {
+            panic!("{wsh}")
+        };
+        let script = inner.redeem_script(0).unwrap();
+        assert_eq!(&script[..1], [OP_1]);
+        assert_eq!(&script[script.len() - 3..], [1, 20, OP_CHECKMULTISIG]);
+        assert!(wsh.address(0, Network::Mainnet).is_ok());
+    }
+
+    #[test]
+    fn invalid() {
+        let g = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        [
+            format!("pkh({g}"),
+            format!("wpkh({g})#89f8spxm"),
+            format!("wsh(wpkh({g}))"),
+            format!("sh(sh(wpkh({g})))"),
+            format!("wpkh(tr({g}))"),
+            format!("wsh(wsh(pkh({g})))"),
+            "tr(eefdea4cdb677750a420fee807eacf21eb9898ae79b9768766e4faa04a2d4a34)".into(),
+            format!("multi(2,{g})"),
+            format!("multi(0,{g})"),
+            format!("foo({g})"),
+        ]
+        .iter()
+        .for_each(|s| assert!(s.parse::<Descriptor>().is_err(), "{s}"));
+    }
+}
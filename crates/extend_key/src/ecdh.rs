@@ -0,0 +1,143 @@
+use crate::curve::EcdsaCurve;
+use crate::ecdsa_key::{PrvKey, PrvKeyBytes, PubKeyBytes, KEY_SIZE};
+use crate::ExtendError;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+pub const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+const HKDF_INFO: &[u8] = b"extend_key ecies";
+
+/// Bytes added to the plain text: `ephemeral public key || nonce || ... || tag`
+pub const ECIES_OVERHEAD: usize = KEY_SIZE + 1 + NONCE_SIZE + TAG_SIZE;
+
+impl<C: EcdsaCurve> PrvKeyBytes<C> {
+    /// x coordinate of the shared point.
+    pub fn ecdh_x(&self, other: &PubKeyBytes<C>) -> Result<[u8; KEY_SIZE], ExtendError> {
+        let shared = Zeroizing::new(C::multiply(other.as_ref(), self.as_ref())?);
+        shared[1..].try_into().map_err(|_| ExtendError::WrongLength)
+    }
+
+    /// SHA-256 of the compressed shared point, same as `secp256k1_ecdh` of libsecp256k1.
+    pub fn ecdh(&self, other: &PubKeyBytes<C>) -> Result<[u8; 32], ExtendError> {
+        let shared = Zeroizing::new(C::multiply(other.as_ref(), self.as_ref())?);
+        Ok(Sha256::digest(shared.as_ref()).into())
+    }
+
+    /// Opens the data made by `encrypt` for the public key of this key.
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, ExtendError> {
+        if data.len() < ECIES_OVERHEAD {
+            return Err(ExtendError::DecryptionFailed);
+        }
+        let (ephemeral, rest) = data.split_at(KEY_SIZE + 1);
+        let (nonce, cipher_text) = rest.split_at(NONCE_SIZE);
+        let ephemeral: PubKeyBytes<C> = ephemeral.try_into()?;
+        cipher(self, &ephemeral, &ephemeral)?
+            .decrypt(Nonce::from_slice(nonce), cipher_text)
+            .map_err(|_| ExtendError::DecryptionFailed)
+    }
+}
+
+/// ECIES with a random ephemeral key: ECDH, HKDF-SHA256 and AES-256-GCM.
+pub fn encrypt<C: EcdsaCurve>(
+    recipient: &PubKeyBytes<C>,
+    plain_text: &[u8],
+) -> Result<Vec<u8>, ExtendError> {
+    let ephemeral = loop {
+        let key: PrvKeyBytes<C> = random_bytes::<KEY_SIZE>()?.as_ref().try_into()?;
+        if C::is_valid_scalar(key.as_ref()) {
+            break key;
+        }
+    };
+    encrypt_with(recipient, &ephemeral, &random_bytes()?, plain_text)
+}
+
+/// `encrypt` with the given ephemeral key and nonce, which must not be reused.
+pub fn encrypt_with<C: EcdsaCurve>(
+    recipient: &PubKeyBytes<C>,
+    ephemeral: &PrvKeyBytes<C>,
+    nonce: &[u8; NONCE_SIZE],
+    plain_text: &[u8],
+) -> Result<Vec<u8>, ExtendError> {
+    let public = ephemeral.get_public()?;
+    let cipher_text = cipher(ephemeral, recipient, &public)?
+        .encrypt(Nonce::from_slice(nonce), plain_text)
+        .map_err(|_| ExtendError::InvalidFormat("plain text"))?;
+    Ok([public.as_ref(), nonce, &cipher_text].concat())
+}
+
+/// The key is derived from the shared x coordinate, salted with the ephemeral public key.
+fn cipher<C: EcdsaCurve>(
+    key: &PrvKeyBytes<C>,
+    other: &PubKeyBytes<C>,
+    ephemeral: &PubKeyBytes<C>,
+) -> Result<Aes256Gcm, ExtendError> {
+    let shared = Zeroizing::new(key.ecdh_x(other)?);
+    let mut okm = Zeroizing::new([0; 32]);
+    Hkdf::<Sha256>::new(Some(ephemeral.as_ref()), shared.as_ref())
+        .expand(HKDF_INFO, okm.as_mut())
+        .map_err(|_| ExtendError::WrongLength)?;
+    Aes256Gcm::new_from_slice(okm.as_ref()).map_err(|_| ExtendError::WrongLength)
+}
+
+pub(crate) fn random_bytes<const N: usize>() -> Result<[u8; N], ExtendError> {
+    let mut buf = [0; N];
+    getrandom::getrandom(&mut buf).map_err(ExtendError::RandomUnavailable)?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::curve::NistP256;
+    use hex_literal::hex;
+
+    fn key<C: EcdsaCurve>(i: u8) -> PrvKeyBytes<C> {
+        let mut bs = [0; KEY_SIZE];
+        bs[KEY_SIZE - 1] = i;
+        bs.as_ref().try_into().unwrap()
+    }
+
+    #[test]
+    fn shared_secret() {
+        let (a, b): (PrvKeyBytes, PrvKeyBytes) = (key(1), key(2));
+        let (pa, pb) = (a.get_public().unwrap(), b.get_public().unwrap());
+        // 2G
+        let x = hex!("c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5");
+        assert_eq!(a.ecdh_x(&pb).unwrap(), x);
+        assert_eq!(b.ecdh_x(&pa).unwrap(), x);
+        assert_eq!(
+            a.ecdh(&pb).unwrap(),
+            <[u8; 32]>::from(Sha256::digest([&[0x02], x.as_ref()].concat()))
+        );
+        assert_eq!(a.ecdh(&pb).unwrap(), b.ecdh(&pa).unwrap());
+    }
+
+    #[test]
+    fn ecies() {
+        let recipient: PrvKeyBytes = key(7);
+        let public = recipient.get_public().unwrap();
+        let data = encrypt(&public, b"hello").unwrap();
+        assert_eq!(data.len(), 5 + ECIES_OVERHEAD);
+        assert_eq!(recipient.decrypt(&data).unwrap(), b"hello");
+        assert!(key::<crate::curve::Secp256k1>(8).decrypt(&data).is_err());
+
+        let mut broken = data.clone();
+        broken[KEY_SIZE + 1 + NONCE_SIZE] ^= 1;
+        assert!(recipient.decrypt(&broken).is_err());
+
+        let fixed = encrypt_with(&public, &key(3), &[0; NONCE_SIZE], b"hello").unwrap();
+        assert_eq!(
+            fixed,
+            encrypt_with(&public, &key(3), &[0; NONCE_SIZE], b"hello").unwrap()
+        );
+        assert_eq!(recipient.decrypt(&fixed).unwrap(), b"hello");
+
+        let recipient: PrvKeyBytes<NistP256> = key(7);
+        let data = encrypt(&recipient.get_public().unwrap(), b"").unwrap();
+        assert_eq!(recipient.decrypt(&data).unwrap(), b"");
+    }
+}
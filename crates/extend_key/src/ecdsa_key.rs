@@ -1,27 +1,40 @@
+use crate::base58;
+use crate::curve::{EcdsaCurve, Secp256k1};
 use crate::fixed_bytes::FixedBytes;
-use crate::local_macro::fixed_bytes;
+use crate::hex;
+use crate::local_macro::{fixed_bytes, secret_bytes};
 use crate::ExtendError;
-use crypto_bigint::{Encoding, U256};
-use elliptic_curve::{group::GroupEncoding, sec1::ToEncodedPoint, Curve, NonZeroScalar};
-use k256::{AffinePoint, Secp256k1};
+use core::fmt;
+use core::marker::PhantomData;
+use core::str::FromStr;
 use ripemd::Ripemd160;
 use sha2::{Digest, Sha256};
 
 pub const KEY_SIZE: usize = 32;
 
-const GENERATOR: AffinePoint = AffinePoint::GENERATOR;
+pub trait KeyBytes: Sized + AsRef<[u8]> {
+    /// True if an invalid child is retried with `0x01 || IR || index` as SLIP-0010.
+    const RETRY_INVALID: bool = false;
 
-const ORDER: U256 = Secp256k1::ORDER;
+    /// `None` if the derived key is invalid for the curve.
+    fn new_child(&self, salt: &[u8]) -> Result<Option<Self>, ExtendError>;
 
-type EcdsaScalar = NonZeroScalar<Secp256k1>;
-
-pub trait KeyBytes: Sized + FixedBytes + AsRef<[u8]> {
-    fn new_child(&self, key: &[u8]) -> Result<Self, ExtendError>;
+    /// Error of the class why the key can not be used on the curve.
+    fn validate(&self) -> Result<(), ExtendError>;
 }
 
 pub trait PrvKey: KeyBytes {
     type Public: PubKey;
 
+    /// HMAC key to derive the master key from a seed.
+    const SEED_KEY: &'static [u8];
+
+    /// True if the key can not derive normal (non-hardened) children.
+    const HARDENED_ONLY: bool = false;
+
+    /// The only prefix allowed for the keys which have no base58 serialization.
+    const FIXED_PREFIX: Option<base58::Prefix> = None;
+
     fn get_public(&self) -> Result<Self::Public, ExtendError>;
 }
 
@@ -29,70 +42,147 @@ pub trait PubKey: KeyBytes {
     fn fingerprint(&self) -> Fingerprint;
 }
 
+/// Key which has the BIP-0032 base58 serialization, unlike SLIP-0010 ed25519 keys.
+pub trait Base58Key: KeyBytes {}
+
 //----------------------------------------------------------------
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Fingerprint([u8; 4]);
 fixed_bytes!(Fingerprint);
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct PrvKeyBytes([u8; KEY_SIZE]);
-fixed_bytes!(PrvKeyBytes);
-
-impl KeyBytes for PrvKeyBytes {
-    fn new_child(&self, salt: &[u8]) -> Result<Self, ExtendError> {
-        let a = U256::from_be_bytes(self.0);
-        let b = U256::from_be_slice(salt);
-        let c = a.add_mod(&b, &ORDER);
-        Ok(Self(c.to_be_bytes()))
+impl Fingerprint {
+    /// First 4 bytes of HASH160 of the serialized public key.
+    pub fn of_key(key: &[u8]) -> Self {
+        let sha = Sha256::digest(key);
+        let ds = Ripemd160::digest(sha);
+        Self(ds[..4].try_into().expect("taken 4 bytes must be 4 bytes"))
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(&self.0))
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = ExtendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        hex::decode(s, "Fingerprint")?.as_slice().try_into()
     }
 }
 
-impl PrvKey for PrvKeyBytes {
-    type Public = PubKeyBytes;
+#[derive(Clone)]
+pub struct PrvKeyBytes<C: EcdsaCurve = Secp256k1>([u8; KEY_SIZE], PhantomData<C>);
+secret_bytes!(PrvKeyBytes<C: EcdsaCurve>);
+
+impl<C: EcdsaCurve> KeyBytes for PrvKeyBytes<C> {
+    const RETRY_INVALID: bool = C::RETRY_INVALID;
+
+    fn new_child(&self, salt: &[u8]) -> Result<Option<Self>, ExtendError> {
+        let c = C::add_scalar(self.as_ref(), salt)?;
+        Ok(c.map(|bs| Self(bs, PhantomData)))
+    }
+
+    fn validate(&self) -> Result<(), ExtendError> {
+        if !C::is_valid_scalar(self.as_ref()) {
+            return Err(ExtendError::InvalidPrivateKey);
+        }
+        Ok(())
+    }
+}
+
+impl<C: EcdsaCurve> PrvKeyBytes<C> {
+    /// Inverse of `new_child`.
+    pub(crate) fn parent_of(&self, salt: &[u8]) -> Result<Option<Self>, ExtendError> {
+        let c = C::sub_scalar(self.as_ref(), salt)?;
+        Ok(c.map(|bs| Self(bs, PhantomData)))
+    }
+}
+
+impl<C: EcdsaCurve> Base58Key for PrvKeyBytes<C> {}
+
+impl<C: EcdsaCurve> PrvKey for PrvKeyBytes<C> {
+    type Public = PubKeyBytes<C>;
+
+    const SEED_KEY: &'static [u8] = C::SEED_KEY;
 
     fn get_public(&self) -> Result<Self::Public, ExtendError> {
-        let a = EcdsaScalar::try_from(self.as_ref())?;
-        let b = GENERATOR * *a;
-        Ok(b.to_bytes().as_slice().try_into()?)
+        Ok(PubKeyBytes(C::public_key(self.as_ref())?, PhantomData))
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct PubKeyBytes([u8; KEY_SIZE + 1]);
-fixed_bytes!(PubKeyBytes);
-
-impl PubKeyBytes {
-    fn to_point(&self) -> AffinePoint {
-        let bs = self.as_ref().into();
-        let o: Option<_> = AffinePoint::from_bytes(bs).into();
-        o.expect("Wrong bytes should not be here")
-    }
+pub struct PubKeyBytes<C: EcdsaCurve = Secp256k1>([u8; KEY_SIZE + 1], PhantomData<C>);
+fixed_bytes!(PubKeyBytes<C: EcdsaCurve>);
 
-    pub fn uncompressed_bytes(&self) -> [u8; KEY_SIZE * 2 + 1] {
-        let point = self.to_point();
-        let encoded = point.to_encoded_point(false);
-        println!("length of encoded point: {:?}", encoded.len());
-        encoded
-            .as_bytes()
-            .try_into()
-            .expect("Should be valid length")
+impl<C: EcdsaCurve> PubKeyBytes<C> {
+    pub fn uncompressed_bytes(&self) -> Result<[u8; KEY_SIZE * 2 + 1], ExtendError> {
+        C::uncompress(self.as_ref())
     }
 }
 
-impl KeyBytes for PubKeyBytes {
-    fn new_child(&self, salt: &[u8]) -> Result<Self, ExtendError> {
-        let a = EcdsaScalar::try_from(salt)?;
-        let b = GENERATOR * *a;
-        let c = b + self.to_point();
-        Ok(c.to_bytes().as_slice().try_into()?)
+impl<C: EcdsaCurve> KeyBytes for PubKeyBytes<C> {
+    const RETRY_INVALID: bool = C::RETRY_INVALID;
+
+    fn new_child(&self, salt: &[u8]) -> Result<Option<Self>, ExtendError> {
+        let c = C::add_point(self.as_ref(), salt)?;
+        Ok(c.map(|bs| Self(bs, PhantomData)))
+    }
+
+    fn validate(&self) -> Result<(), ExtendError> {
+        if self.0[0] == 0 {
+            return Err(ExtendError::TypeMismatched);
+        }
+        if !C::is_valid_point(self.as_ref()) {
+            return Err(ExtendError::InvalidPublicKey);
+        }
+        Ok(())
     }
 }
 
-impl PubKey for PubKeyBytes {
+impl<C: EcdsaCurve> Base58Key for PubKeyBytes<C> {}
+
+impl<C: EcdsaCurve> PubKey for PubKeyBytes<C> {
     fn fingerprint(&self) -> Fingerprint {
-        let sha = Sha256::digest(self.as_ref());
-        let ds = Ripemd160::digest(&sha);
-        ds[..4].try_into().expect("taken 4 bytes must be 4 bytes")
+        Fingerprint::of_key(self.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    /// Order of secp256k1.
+    const N: [u8; 32] = hex!("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+    const N_MINUS_1: [u8; 32] =
+        hex!("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364140");
+
+    fn one() -> PrvKeyBytes {
+        let mut bs = [0; KEY_SIZE];
+        bs[KEY_SIZE - 1] = 1;
+        bs.as_ref().try_into().unwrap()
+    }
+
+    #[test]
+    fn invalid_child_of_prvkey() {
+        // IL >= n
+        assert!(one().new_child(&N).unwrap().is_none());
+        // k_par + IL = 0
+        assert!(one().new_child(&N_MINUS_1).unwrap().is_none());
+        assert!(one().new_child(&one().0).unwrap().is_some());
+    }
+
+    #[test]
+    fn invalid_child_of_pubkey() {
+        let public = one().get_public().unwrap();
+        // IL >= n
+        assert!(public.new_child(&N).unwrap().is_none());
+        // IL*G + K_par is the point at infinity
+        assert!(public.new_child(&N_MINUS_1).unwrap().is_none());
+        assert!(public.new_child(&one().0).unwrap().is_some());
     }
 }
@@ -0,0 +1,100 @@
+use crate::base58::Prefix;
+use crate::ecdsa_key::{Fingerprint, KeyBytes, PrvKey, PubKey, KEY_SIZE};
+use crate::fixed_bytes::FixedBytes;
+use crate::local_macro::{fixed_bytes, secret_bytes};
+use crate::ExtendError;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+
+/// SLIP-0010 ed25519 private key.
+/// Children are always hardened and the key is taken from IL as is.
+#[derive(Clone)]
+pub struct Ed25519PrvKeyBytes([u8; KEY_SIZE]);
+secret_bytes!(Ed25519PrvKeyBytes);
+
+impl KeyBytes for Ed25519PrvKeyBytes {
+    fn new_child(&self, salt: &[u8]) -> Result<Option<Self>, ExtendError> {
+        Ok(Some(salt.try_into()?))
+    }
+
+    fn validate(&self) -> Result<(), ExtendError> {
+        Ok(())
+    }
+}
+
+impl PrvKey for Ed25519PrvKeyBytes {
+    type Public = Ed25519PubKeyBytes;
+
+    const SEED_KEY: &'static [u8] = b"ed25519 seed";
+    const HARDENED_ONLY: bool = true;
+    const FIXED_PREFIX: Option<Prefix> = Some(Prefix::ED25519_PRV);
+
+    fn get_public(&self) -> Result<Self::Public, ExtendError> {
+        let key = SigningKey::from_bytes(&self.0).verifying_key();
+        let mut bs = [0; KEY_SIZE + 1];
+        bs[1..].copy_from_slice(key.as_bytes());
+        Ok(Ed25519PubKeyBytes(bs))
+    }
+}
+
+/// ed25519 public key prefixed with 0x00 to be 33 bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ed25519PubKeyBytes([u8; KEY_SIZE + 1]);
+fixed_bytes!(Ed25519PubKeyBytes);
+
+impl Ed25519PubKeyBytes {
+    pub fn raw_bytes(&self) -> &[u8] {
+        &self.0[1..]
+    }
+}
+
+impl KeyBytes for Ed25519PubKeyBytes {
+    fn new_child(&self, _salt: &[u8]) -> Result<Option<Self>, ExtendError> {
+        Err(ExtendError::HardenedOnly)
+    }
+
+    fn validate(&self) -> Result<(), ExtendError> {
+        let [0, key @ ..] = &self.0 else {
+            return Err(ExtendError::InvalidPublicKey);
+        };
+        VerifyingKey::from_bytes(key).map_err(|_| ExtendError::InvalidPublicKey)?;
+        Ok(())
+    }
+}
+
+impl PubKey for Ed25519PubKeyBytes {
+    fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::of_key(self.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecdsa_key::PrvKeyBytes;
+    use crate::extkey::ExtKey;
+    use hdpath::node::Node;
+    use hex_literal::hex;
+
+    #[test]
+    fn no_normal_child() {
+        let m: ExtKey<Ed25519PrvKeyBytes> = ExtKey::from_seed(
+            Prefix::ED25519_PRV,
+            hex!("000102030405060708090a0b0c0d0e0f").as_ref(),
+        )
+        .unwrap();
+        assert!(m.get_child(Node::Normal(0)).is_err());
+        assert!(m.get_child(Node::Hardened(0)).is_ok());
+
+        let p = m.get_public().unwrap();
+        assert!(p.get_child_normal_only(Node::Normal(0)).is_err());
+    }
+
+    #[test]
+    fn fixed_prefix() {
+        let seed = hex!("000102030405060708090a0b0c0d0e0f");
+        let m = ExtKey::<Ed25519PrvKeyBytes>::from_seed(Prefix::ED25519_PRV, &seed).unwrap();
+        assert_eq!(m.get_public().unwrap().prefix, Prefix::ED25519_PUB);
+        assert!(ExtKey::<Ed25519PrvKeyBytes>::from_seed(Prefix::XPRV, &seed).is_err());
+        assert!(ExtKey::<PrvKeyBytes>::from_seed(Prefix::ED25519_PRV, &seed).is_err());
+    }
+}
@@ -1,45 +1,148 @@
+use core::fmt;
+use hdpath::node::Node;
+use hdpath::path::HDPathError;
 use hmac::digest::InvalidLength;
 
 #[derive(Debug)]
-pub struct ExtendError(String);
-
-impl ExtendError {
-    pub fn invalid_hdpath() -> ExtendError {
-        ExtendError("Invalid hdpath".to_owned())
-    }
+#[non_exhaustive]
+pub enum ExtendError {
+    /// Path has no node or is rejected by `HDPath` itself.
+    InvalidHDPath(Option<HDPathError>),
+    /// Depth is over 255.
+    DepthOverflow,
+    WrongLength,
+    /// Public key can not derive hardened child.
+    HardenedFromPublic,
+    /// Key can derive only hardened child, as ed25519.
+    HardenedOnly,
+    /// IL is not less than the order or the child key is zero (or the point at infinity),
+    /// so the index must be skipped as BIP-0032.
+    InvalidChild(Node),
+    NotAChild,
+    InvalidSeed,
+    /// Zero or not less than the order of the curve.
+    InvalidPrivateKey,
+    /// Not a point on the curve.
+    InvalidPublicKey,
+    /// Master key (depth 0) with non-zero parent fingerprint.
+    ZeroDepthWithParent,
+    /// Master key (depth 0) with non-zero child index.
+    ZeroDepthWithIndex,
+    /// Malformed bytes or string of the target.
+    InvalidFormat(&'static str),
+    InvalidChecksum,
+    /// Script is not a standard form which has an address.
+    NoAddress,
+    Base58(bs58::decode::Error),
+    /// Private key is required but public key is given, or vice versa.
+    TypeMismatched,
+    UnknownVersion,
+    DuplicatedPrefix(String),
+    InvalidSignature,
+    /// Public key is not one of the aggregated keys of MuSig2.
+    NotASigner,
+    /// BIP-0328 derivation of the MuSig2 aggregate key after the other tweaks.
+    AlreadyTweaked,
+    RandomUnavailable(getrandom::Error),
+    DecryptionFailed,
+    Curve(elliptic_curve::Error),
+    Hmac(InvalidLength),
+}
 
-    pub fn depth_exceeded() -> ExtendError {
-        ExtendError("Exceeded depth".to_owned())
+impl fmt::Display for ExtendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHDPath(_) => f.write_str("Invalid hdpath"),
+            Self::DepthOverflow => f.write_str("Exceeded depth"),
+            Self::WrongLength => f.write_str("Wrong length bytes"),
+            Self::HardenedFromPublic => f.write_str("Public key can not derive hardened key"),
+            Self::HardenedOnly => f.write_str("Only hardened child can be derived"),
+            Self::InvalidChild(node) => write!(f, "Derived child key is invalid at {node}"),
+            Self::NotAChild => f.write_str("Key is not a normal child of the extended public key"),
+            Self::InvalidSeed => f.write_str("Seed derives invalid master key"),
+            Self::InvalidPrivateKey => f.write_str("Private key is out of range"),
+            Self::InvalidPublicKey => f.write_str("Public key is not on the curve"),
+            Self::ZeroDepthWithParent => f.write_str("Zero depth with non-zero parent fingerprint"),
+            Self::ZeroDepthWithIndex => f.write_str("Zero depth with non-zero child index"),
+            Self::InvalidFormat(target) => write!(f, "Invalid bytes format for {target}"),
+            Self::InvalidChecksum => f.write_str("Checksum miss-matched"),
+            Self::NoAddress => f.write_str("Script has no address"),
+            Self::Base58(_) => f.write_str("Invalid Base58"),
+            Self::TypeMismatched => f.write_str("Type miss-matched"),
+            Self::UnknownVersion => f.write_str("unsupported version"),
+            Self::DuplicatedPrefix(label) => write!(f, "Prefix is already registered: {label}"),
+            Self::InvalidSignature => f.write_str("Signature verification failed"),
+            Self::NotASigner => f.write_str("Public key is not one of the signers"),
+            Self::AlreadyTweaked => f.write_str("Aggregate key is already tweaked"),
+            Self::RandomUnavailable(_) => f.write_str("Random bytes are not available"),
+            Self::DecryptionFailed => f.write_str("Failed to decrypt"),
+            Self::Curve(_) => f.write_str("Elliptic curve error"),
+            Self::Hmac(_) => f.write_str("Invalid HMAC key"),
+        }
     }
+}
 
-    pub fn wrong_length_bytes() -> ExtendError {
-        ExtendError("Wrong length bytes".to_owned())
+impl std::error::Error for ExtendError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidHDPath(Some(src)) => Some(src),
+            Self::Base58(src) => Some(src),
+            Self::RandomUnavailable(src) => Some(src),
+            Self::Curve(src) => Some(src),
+            Self::Hmac(src) => Some(src),
+            _ => None,
+        }
     }
+}
 
-    pub fn cannot_hardened() -> ExtendError {
-        ExtendError("Public key can not derive hardened key".to_owned())
+impl From<InvalidLength> for ExtendError {
+    fn from(src: InvalidLength) -> Self {
+        Self::Hmac(src)
     }
+}
 
-    pub fn invalid_format(target: &str) -> ExtendError {
-        ExtendError(format!("Invalid bytes format for {target}"))
+impl From<elliptic_curve::Error> for ExtendError {
+    fn from(src: elliptic_curve::Error) -> Self {
+        Self::Curve(src)
     }
+}
 
-    pub fn type_missmatched() -> ExtendError {
-        ExtendError("Type miss-matched".to_owned())
+impl From<HDPathError> for ExtendError {
+    fn from(src: HDPathError) -> Self {
+        Self::InvalidHDPath(Some(src))
     }
+}
 
-    pub fn unsupported_version() -> ExtendError {
-        ExtendError("unsupported version".to_owned())
+impl From<bs58::decode::Error> for ExtendError {
+    fn from(src: bs58::decode::Error) -> Self {
+        match src {
+            bs58::decode::Error::InvalidChecksum { .. } => Self::InvalidChecksum,
+            bs58::decode::Error::BufferTooSmall => Self::WrongLength,
+            _ => Self::Base58(src),
+        }
     }
 }
 
-impl From<InvalidLength> for ExtendError {
-    fn from(src: InvalidLength) -> Self {
-        Self(src.to_string())
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::base58;
+    use std::error::Error;
+
+    #[test]
+    fn checksum() {
+        let s = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHj";
+        assert!(matches!(
+            base58::decode(s),
+            Err(ExtendError::InvalidChecksum)
+        ));
     }
-}
-impl From<elliptic_curve::Error> for ExtendError {
-    fn from(src: elliptic_curve::Error) -> Self {
-        Self(src.to_string())
+
+    #[test]
+    fn source() {
+        let err = base58::decode("0OIl").unwrap_err();
+        assert!(matches!(err, ExtendError::Base58(_)));
+        assert!(err.source().is_some());
+        assert!(ExtendError::WrongLength.source().is_none());
     }
 }
@@ -1,30 +1,32 @@
 use crate::base58;
-use crate::ecdsa_key::{Fingerprint, KeyBytes, PrvKey, PrvKeyBytes, PubKey, PubKeyBytes, KEY_SIZE};
+use crate::curve::EcdsaCurve;
+use crate::ecdsa_key::{
+    Base58Key, Fingerprint, KeyBytes, PrvKey, PrvKeyBytes, PubKey, PubKeyBytes, KEY_SIZE,
+};
 use crate::fixed_bytes::FixedBytes;
-use crate::local_macro::fixed_bytes;
+use crate::local_macro::{fixed_bytes, secret_bytes};
 use crate::ExtendError;
-use bytes::Bytes;
 use core::fmt;
 use hdpath::node::Node;
 use hdpath::path::HDPath;
+use hdpath::policy::PathPolicy;
 use hmac::{Hmac, Mac};
 use sha2::Sha512;
+use zeroize::Zeroizing;
 
 type HmacSha512 = Hmac<Sha512>;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Clone)]
 pub struct ChainCode([u8; KEY_SIZE]);
-fixed_bytes!(ChainCode);
+secret_bytes!(ChainCode);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Depth([u8; 1]);
 fixed_bytes!(Depth);
 
 impl Depth {
-    fn increment(&self) -> Result<Self, ExtendError> {
-        let next = self.0[0]
-            .checked_add(1)
-            .ok_or(ExtendError::depth_exceeded())?;
+    pub(crate) fn increment(&self) -> Result<Self, ExtendError> {
+        let next = self.0[0].checked_add(1).ok_or(ExtendError::DepthOverflow)?;
         Ok(Self([next]))
     }
 }
@@ -39,9 +41,35 @@ impl From<u32> for ChildNumber {
     }
 }
 
+impl From<&ChildNumber> for Node {
+    fn from(src: &ChildNumber) -> Self {
+        Node::from(u32::from_be_bytes(src.0))
+    }
+}
+
+/// Calls `f` with `node`, and then with the next index of the same kind while the child is invalid.
+fn skip_invalid<T>(
+    mut node: Node,
+    f: impl Fn(Node) -> Result<T, ExtendError>,
+) -> Result<T, ExtendError> {
+    loop {
+        match f(node) {
+            Err(ExtendError::InvalidChild(_)) => {
+                node = node
+                    .raw_index()
+                    .checked_add(1)
+                    .map(Node::from)
+                    .filter(|next| next.is_hardened() == node.is_hardened())
+                    .ok_or(ExtendError::InvalidChild(node))?;
+            }
+            r => return r,
+        }
+    }
+}
+
 //----------------------------------------------------------------
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ExtKey<A> {
     pub prefix: base58::Prefix,
     pub parent: Fingerprint,
@@ -51,38 +79,97 @@ pub struct ExtKey<A> {
     pub child_number: ChildNumber,
 }
 
-impl ExtKey<PrvKeyBytes> {
-    pub fn from_seed(prefix: base58::Prefix, seed: Bytes) -> Result<Self, ExtendError> {
+impl<A> ExtKey<A> {
+    pub fn get_key(&self) -> &A {
+        &self.key
+    }
+}
+
+impl<A> ExtKey<A>
+where
+    A: PrvKey,
+    A: for<'a> TryFrom<&'a [u8], Error = ExtendError>,
+{
+    pub fn from_seed(prefix: base58::Prefix, seed: &[u8]) -> Result<Self, ExtendError> {
+        prefix.validate()?;
         if prefix.is_public() {
-            return Err(ExtendError::type_missmatched());
+            return Err(ExtendError::TypeMismatched);
         }
-        let mut hash = HmacSha512::new_from_slice("Bitcoin seed".as_bytes())?;
-        hash.update(&seed);
-        let hashed = &hash.finalize().into_bytes();
-        let (child_key, chain_code) = hashed.split_at(hashed.len() / 2);
+        match &A::FIXED_PREFIX {
+            Some(fixed) if fixed != &prefix => return Err(ExtendError::TypeMismatched),
+            None if prefix == base58::Prefix::ED25519_PRV => {
+                return Err(ExtendError::TypeMismatched)
+            }
+            _ => {}
+        }
+        let mut data = Zeroizing::new(seed.to_vec());
+        let (key, chain_code) = loop {
+            let mut hash = HmacSha512::new_from_slice(A::SEED_KEY)?;
+            hash.update(&data);
+            let hashed = Zeroizing::new(<[u8; KEY_SIZE * 2]>::from(hash.finalize().into_bytes()));
+            let (child_key, chain_code) = hashed.split_at(hashed.len() / 2);
+            let key: A = child_key.try_into()?;
+            if key.validate().is_ok() {
+                break (key, chain_code.try_into()?);
+            }
+            if !A::RETRY_INVALID {
+                return Err(ExtendError::InvalidSeed);
+            }
+            data = Zeroizing::new(hashed.to_vec());
+        };
         let result = ExtKey {
             prefix,
             parent: [0, 0, 0, 0].as_ref().try_into()?,
-            chain_code: chain_code.try_into()?,
-            key: child_key.try_into()?,
+            chain_code,
+            key,
             depth: [0].as_ref().try_into()?,
             child_number: 0.into(),
         };
         Ok(result)
     }
+}
 
-    pub fn get_key(&self) -> &PrvKeyBytes {
-        &self.key
+impl<A: KeyBytes> ExtKey<A> {
+    /// `HMAC-SHA512(c_par, data || ser32(i))`
+    pub(crate) fn hmac_child(
+        &self,
+        data: &[u8],
+        child_number: &ChildNumber,
+    ) -> Result<[u8; KEY_SIZE * 2], ExtendError> {
+        let mut hash = HmacSha512::new_from_slice(self.chain_code.as_ref())?;
+        hash.update(data);
+        hash.update(child_number.as_ref());
+        Ok(hash.finalize().into_bytes().into())
     }
-}
 
-impl ExtKey<PubKeyBytes> {
-    pub fn get_key(&self) -> &PubKeyBytes {
-        &self.key
+    /// CKD step from the serialized parent key, returns the child key, the chain code
+    /// and the left half of the hash which made the child.
+    fn ckd<K: AsRef<[u8]>>(
+        &self,
+        child_number: &ChildNumber,
+        key: &K,
+    ) -> Result<(A, ChainCode, Zeroizing<Vec<u8>>), ExtendError> {
+        let key_bytes = key.as_ref();
+        let padding = vec![0; (KEY_SIZE + 1) - key_bytes.len()];
+        let mut data = Zeroizing::new([&padding, key_bytes].concat());
+
+        loop {
+            let hashed = Zeroizing::new(self.hmac_child(&data, child_number)?);
+            let (child_key, chain_code) = hashed.split_at(hashed.len() / 2);
+            if let Some(key) = self.key.new_child(child_key)? {
+                return Ok((
+                    key,
+                    chain_code.try_into()?,
+                    Zeroizing::new(child_key.to_vec()),
+                ));
+            }
+            if !A::RETRY_INVALID {
+                return Err(ExtendError::InvalidChild(child_number.into()));
+            }
+            data = Zeroizing::new([&[1], chain_code].concat());
+        }
     }
-}
 
-impl<A: KeyBytes> ExtKey<A> {
     fn mk_child<K: AsRef<[u8]>>(
         &self,
         prefix: base58::Prefix,
@@ -90,21 +177,12 @@ impl<A: KeyBytes> ExtKey<A> {
         child_number: ChildNumber,
         key: &K,
     ) -> Result<Self, ExtendError> {
-        let key_bytes = key.as_ref();
-        let padding = vec![0; (KEY_SIZE + 1) - key_bytes.len()];
-
-        let mut hash = HmacSha512::new_from_slice(self.chain_code.as_ref())?;
-        hash.update(&padding);
-        hash.update(key_bytes);
-        hash.update(child_number.as_ref());
-        let hashed = &hash.finalize().into_bytes();
-
-        let (child_key, chain_code) = hashed.split_at(hashed.len() / 2);
+        let (key, chain_code, _) = self.ckd(&child_number, key)?;
         let next = ExtKey {
             prefix,
             parent,
-            chain_code: chain_code.try_into()?,
-            key: self.key.new_child(child_key)?,
+            chain_code,
+            key,
             depth: self.depth.increment()?,
             child_number,
         };
@@ -115,7 +193,7 @@ impl<A: KeyBytes> ExtKey<A> {
 impl<A: PubKey> ExtKey<A> {
     pub fn get_child_normal_only(&self, node: Node) -> Result<Self, ExtendError> {
         if node.is_hardened() {
-            return Err(ExtendError::cannot_hardened());
+            return Err(ExtendError::HardenedFromPublic);
         }
         self.mk_child(
             self.prefix.clone(),
@@ -124,6 +202,11 @@ impl<A: PubKey> ExtKey<A> {
             &self.key,
         )
     }
+
+    /// `get_child_normal_only`, but moves on to the next index if the child is invalid.
+    pub fn get_child_normal_only_or_next(&self, node: Node) -> Result<Self, ExtendError> {
+        skip_invalid(node, |n| self.get_child_normal_only(n))
+    }
 }
 
 impl<A, B> ExtKey<A>
@@ -132,16 +215,23 @@ where
     B: PubKey,
 {
     pub fn get_child(&self, node: Node) -> Result<Self, ExtendError> {
-        let fp = self.key.get_public()?.fingerprint();
+        self.get_child_with_public(node, &self.key.get_public()?)
+    }
+
+    /// `get_child` with the public key of this key computed beforehand.
+    pub(crate) fn get_child_with_public(
+        &self,
+        node: Node,
+        public: &B,
+    ) -> Result<Self, ExtendError> {
+        if A::HARDENED_ONLY && node.is_normal() {
+            return Err(ExtendError::HardenedOnly);
+        }
+        let fp = public.fingerprint();
         if node.is_hardened() {
             self.mk_child(self.prefix.clone(), fp, node.raw_index().into(), &self.key)
         } else {
-            self.mk_child(
-                self.prefix.clone(),
-                fp,
-                node.raw_index().into(),
-                &self.key.get_public()?,
-            )
+            self.mk_child(self.prefix.clone(), fp, node.raw_index().into(), public)
         }
     }
 
@@ -151,10 +241,38 @@ where
                 prev.and_then(|parent| parent.get_child(*node))
             })
         } else {
-            Err(ExtendError::invalid_hdpath())
+            Err(ExtendError::InvalidHDPath(None))
+        }
+    }
+
+    /// `get_child`, but moves on to the next index if the child is invalid.
+    /// The index actually used is in `child_number` of the result.
+    pub fn get_child_or_next(&self, node: Node) -> Result<Self, ExtendError> {
+        let public = self.key.get_public()?;
+        skip_invalid(node, |n| self.get_child_with_public(n, &public))
+    }
+
+    /// `derive_child` with `get_child_or_next` for every node.
+    pub fn derive_child_or_next(&self, path: HDPath) -> Result<Self, ExtendError> {
+        if let [head, tail @ ..] = path.nodes() {
+            tail.iter()
+                .fold(self.get_child_or_next(*head), |prev, node| {
+                    prev.and_then(|parent| parent.get_child_or_next(*node))
+                })
+        } else {
+            Err(ExtendError::InvalidHDPath(None))
         }
     }
 
+    pub fn derive_child_with_policy(
+        &self,
+        path: HDPath,
+        policy: &PathPolicy,
+    ) -> Result<Self, ExtendError> {
+        policy.check(&path)?;
+        self.derive_child(path)
+    }
+
     pub fn get_public(&self) -> Result<ExtKey<B>, ExtendError> {
         let r = ExtKey {
             prefix: self.prefix.get_public()?,
@@ -168,25 +286,89 @@ where
     }
 }
 
+impl<C: EcdsaCurve> ExtKey<PubKeyBytes<C>> {
+    /// Computes the private key of this public key from a private key derived from it by
+    /// the relative `path`, as `k_par = k_i - IL` for each step back up the chain.
+    /// Every node of the path must be normal.
+    pub fn recover_parent(
+        &self,
+        path: &[Node],
+        child: &ExtKey<PrvKeyBytes<C>>,
+    ) -> Result<ExtKey<PrvKeyBytes<C>>, ExtendError> {
+        if path.is_empty() || path.iter().any(|n| n.is_hardened()) {
+            return Err(ExtendError::NotAChild);
+        }
+        let mut xpubs = vec![self.clone()];
+        for node in &path[..path.len() - 1] {
+            let next = xpubs[xpubs.len() - 1].get_child_normal_only(*node)?;
+            xpubs.push(next);
+        }
+        xpubs
+            .iter()
+            .zip(path)
+            .rev()
+            .try_fold(child.clone(), |key, (xpub, node)| {
+                xpub.recover_direct_parent(*node, &key)
+            })
+    }
+
+    /// `recover_parent` by a direct child at `node`.
+    fn recover_direct_parent(
+        &self,
+        node: Node,
+        child: &ExtKey<PrvKeyBytes<C>>,
+    ) -> Result<ExtKey<PrvKeyBytes<C>>, ExtendError> {
+        if Node::from(&child.child_number) != node
+            || child.parent != self.key.fingerprint()
+            || child.depth != self.depth.increment()?
+        {
+            return Err(ExtendError::NotAChild);
+        }
+        let (public, chain_code, il) = self.ckd(&child.child_number, &self.key)?;
+        if chain_code != child.chain_code || public != child.key.get_public()? {
+            return Err(ExtendError::NotAChild);
+        }
+        let key = child
+            .key
+            .parent_of(&il)?
+            .ok_or(ExtendError::InvalidChild(node))?;
+        let r = ExtKey {
+            prefix: child.prefix.clone(),
+            parent: self.parent.clone(),
+            chain_code: self.chain_code.clone(),
+            key,
+            depth: self.depth.clone(),
+            child_number: self.child_number.clone(),
+        };
+        Ok(r)
+    }
+
+    /// True if sharing this key together with the private key derived by the relative `path`
+    /// exposes the private key of this key.
+    pub fn is_exposed_by(&self, path: &[Node], child: &ExtKey<PrvKeyBytes<C>>) -> bool {
+        self.recover_parent(path, child).is_ok()
+    }
+}
+
 //----------------------------------------------------------------
 
-impl<A: KeyBytes> From<&ExtKey<A>> for base58::DecodedExtKey {
+impl<A: Base58Key> From<&ExtKey<A>> for base58::DecodedExtKey {
     fn from(src: &ExtKey<A>) -> Self {
         base58::DecodedExtKey {
             prefix: src.prefix.clone(),
             depth: src.depth.copy_bytes(),
             parent: src.parent.copy_bytes(),
             child_number: src.child_number.copy_bytes(),
-            chain_code: src.chain_code.copy_bytes(),
-            key: src.key.copy_bytes(),
+            chain_code: Zeroizing::new(src.chain_code.as_ref().to_vec()),
+            key: Zeroizing::new(src.key.as_ref().to_vec()),
         }
     }
 }
 
 impl<A> TryFrom<base58::DecodedExtKey> for ExtKey<A>
 where
-    A: KeyBytes,
-    A: TryFrom<bytes::Bytes, Error = ExtendError>,
+    A: Base58Key,
+    A: for<'a> TryFrom<&'a [u8], Error = ExtendError>,
 {
     type Error = ExtendError;
 
@@ -196,14 +378,15 @@ where
             depth: src.depth.try_into()?,
             parent: src.parent.try_into()?,
             child_number: src.child_number.try_into()?,
-            chain_code: src.chain_code.try_into()?,
-            key: src.key.try_into()?,
+            chain_code: src.chain_code.as_slice().try_into()?,
+            key: src.key.as_slice().try_into()?,
         };
+        r.key.validate()?;
         Ok(r)
     }
 }
 
-impl<A: KeyBytes> fmt::Display for ExtKey<A> {
+impl<A: Base58Key> fmt::Display for ExtKey<A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let d: base58::DecodedExtKey = self.into();
         d.fmt(f)
@@ -220,7 +403,7 @@ mod test {
 
     type ExtPrvKey = ExtKey<PrvKeyBytes>;
 
-    fn check(seed: Bytes, expected: &str) {
+    fn check(seed: &[u8], expected: &str) {
         let actual = ExtPrvKey::from_seed(base58::Prefix::XPRV, seed).unwrap();
         assert_eq!(expected, actual.to_string().as_str());
     }
@@ -228,7 +411,7 @@ mod test {
     #[test]
     fn seed_vector1() {
         check(
-            hex!("000102030405060708090a0b0c0d0e0f").as_ref().into(),
+            hex!("000102030405060708090a0b0c0d0e0f").as_ref(),
             "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi"
         );
     }
@@ -236,7 +419,7 @@ mod test {
     #[test]
     fn seed_vector2() {
         check(
-            hex!("fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542").as_ref().into(),
+            hex!("fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542").as_ref(),
             "xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U"
         );
     }
@@ -244,16 +427,117 @@ mod test {
     #[test]
     fn seed_vector3() {
         check(
-            hex!("4b381541583be4423346c643850da4b320e46a87ae3d2a4e6da11eba819cd4acba45d239319ac14f863b8d5ab5a0d0c64d2e8a1e7d1457df2e5a3c51c73235be").as_ref().into(),
+            hex!("4b381541583be4423346c643850da4b320e46a87ae3d2a4e6da11eba819cd4acba45d239319ac14f863b8d5ab5a0d0c64d2e8a1e7d1457df2e5a3c51c73235be").as_ref(),
             "xprv9s21ZrQH143K25QhxbucbDDuQ4naNntJRi4KUfWT7xo4EKsHt2QJDu7KXp1A3u7Bi1j8ph3EGsZ9Xvz9dGuVrtHHs7pXeTzjuxBrCmmhgC6"
         );
     }
 
+    #[test]
+    fn derive_with_policy() {
+        let m = ExtPrvKey::from_seed(
+            base58::Prefix::XPRV,
+            hex!("000102030405060708090a0b0c0d0e0f").as_ref(),
+        )
+        .unwrap();
+        let policy = PathPolicy::bip44();
+        assert_eq!(
+            m.derive_child("m/44'/60'/0'/0/0".parse().unwrap()).unwrap(),
+            m.derive_child_with_policy("m/44'/60'/0'/0/0".parse().unwrap(), &policy)
+                .unwrap()
+        );
+        assert!(m
+            .derive_child_with_policy("m/44'/60'/0/0/0".parse().unwrap(), &policy)
+            .is_err());
+    }
+
     #[test]
     fn seed_vector4() {
         check(
-            hex!("3ddd5602285899a946114506157c7997e5444528f3003f6134712147db19b678").as_ref().into(),
+            hex!("3ddd5602285899a946114506157c7997e5444528f3003f6134712147db19b678").as_ref(),
             "xprv9s21ZrQH143K48vGoLGRPxgo2JNkJ3J3fqkirQC2zVdk5Dgd5w14S7fRDyHH4dWNHUgkvsvNDCkvAwcSHNAQwhwgNMgZhLtQC63zxwhQmRv"
         );
     }
+
+    #[test]
+    fn recover_parent() {
+        let m = ExtPrvKey::from_seed(
+            base58::Prefix::XPRV,
+            hex!("000102030405060708090a0b0c0d0e0f").as_ref(),
+        )
+        .unwrap();
+        let parent = m.derive_child("m/44'/0'/0'".parse().unwrap()).unwrap();
+        let xpub = parent.get_public().unwrap();
+
+        let child = parent.get_child(Node::Normal(5)).unwrap();
+        assert!(xpub.is_exposed_by(&[Node::Normal(5)], &child));
+        assert_eq!(
+            xpub.recover_parent(&[Node::Normal(5)], &child).unwrap(),
+            parent
+        );
+
+        let hardened = parent.get_child(Node::Hardened(5)).unwrap();
+        assert!(!xpub.is_exposed_by(&[Node::Hardened(5)], &hardened));
+        assert!(!xpub.is_exposed_by(&[Node::Normal(5)], &hardened));
+
+        let path = [Node::Normal(5), Node::Normal(0)];
+        let grandchild = child.get_child(Node::Normal(0)).unwrap();
+        assert!(xpub.is_exposed_by(&path, &grandchild));
+        assert_eq!(xpub.recover_parent(&path, &grandchild).unwrap(), parent);
+        assert!(!xpub.is_exposed_by(&[Node::Normal(0)], &grandchild));
+        assert!(!xpub.is_exposed_by(&[Node::Normal(6), Node::Normal(0)], &grandchild));
+
+        let behind_hardened = parent.get_child(Node::Hardened(5)).unwrap();
+        let behind_hardened = behind_hardened.get_child(Node::Normal(0)).unwrap();
+        assert!(!xpub.is_exposed_by(&[Node::Hardened(5), Node::Normal(0)], &behind_hardened));
+
+        let other = m.get_public().unwrap();
+        assert!(!other.is_exposed_by(&[Node::Normal(5)], &child));
+    }
+
+    #[test]
+    fn redacted_debug() {
+        let m = ExtPrvKey::from_seed(
+            base58::Prefix::XPRV,
+            &hex!("000102030405060708090a0b0c0d0e0f"),
+        )
+        .unwrap();
+        let debug = format!("{m:?}");
+        assert!(debug.contains("PrvKeyBytes(..)"), "{debug}");
+        assert!(debug.contains("ChainCode(..)"), "{debug}");
+        assert!(!debug.to_lowercase().contains("e8f32e72"), "{debug}");
+        assert!(!format!("{:?}", base58::DecodedExtKey::from(&m)).contains("key"));
+
+        assert_eq!(m, m.clone());
+        assert_ne!(m, m.get_child(Node::Hardened(0)).unwrap());
+    }
+
+    #[test]
+    fn skip_invalid_child() {
+        let invalid = [Node::Normal(5), Node::Normal(6), Node::Hardened(0)];
+        let f = |node: Node| match invalid.contains(&node) {
+            true => Err(ExtendError::InvalidChild(node)),
+            false => Ok(node),
+        };
+        assert_eq!(skip_invalid(Node::Normal(5), f).unwrap(), Node::Normal(7));
+        assert_eq!(skip_invalid(Node::Normal(4), f).unwrap(), Node::Normal(4));
+        assert_eq!(
+            skip_invalid(Node::Hardened(0), f).unwrap(),
+            Node::Hardened(1)
+        );
+
+        let last = Node::Normal(0x7fffffff);
+        let r = skip_invalid(last, |n| Err::<Node, _>(ExtendError::InvalidChild(n)));
+        assert!(matches!(r, Err(ExtendError::InvalidChild(n)) if n == last));
+
+        let m = ExtPrvKey::from_seed(
+            base58::Prefix::XPRV,
+            hex!("000102030405060708090a0b0c0d0e0f").as_ref(),
+        )
+        .unwrap();
+        let path: HDPath = "m/44'/60'/0'/0/0".parse().unwrap();
+        assert_eq!(
+            m.derive_child_or_next(path.clone()).unwrap(),
+            m.derive_child(path).unwrap()
+        );
+    }
 }
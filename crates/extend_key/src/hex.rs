@@ -0,0 +1,17 @@
+use crate::ExtendError;
+
+pub(crate) fn encode(src: &[u8]) -> String {
+    src.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub(crate) fn decode(src: &str, target: &'static str) -> Result<Vec<u8>, ExtendError> {
+    if !src.len().is_multiple_of(2) || !src.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ExtendError::InvalidFormat(target));
+    }
+    (0..src.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&src[i..(i + 2)], 16).map_err(|_| ExtendError::InvalidFormat(target))
+        })
+        .collect()
+}
@@ -1,9 +1,24 @@
+pub mod any_key;
 pub mod base58;
+pub mod batch;
+pub mod cache;
+pub mod curve;
+pub mod descriptor;
+pub mod ecdh;
 pub mod ecdsa_key;
+pub mod ed25519_key;
 pub mod error;
 pub mod extkey;
 pub mod fixed_bytes;
+pub mod musig;
+pub mod origin;
+pub mod schnorr;
+pub mod signature;
+pub mod wif;
 
+mod hex;
 mod local_macro;
+#[cfg(feature = "serde")]
+mod serde_impl;
 
 pub use error::ExtendError;
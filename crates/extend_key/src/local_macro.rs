@@ -1,17 +1,35 @@
 macro_rules! fixed_bytes {
-    ($t:ident) => {
+    (@convert $t:ident) => {
         impl TryFrom<&[u8]> for $t {
             type Error = crate::ExtendError;
 
             fn try_from(src: &[u8]) -> Result<Self, Self::Error> {
                 Ok(Self(
                     src.try_into()
-                        .map_err(|_| crate::ExtendError::wrong_length_bytes())?,
+                        .map_err(|_| crate::ExtendError::WrongLength)?,
                 ))
             }
         }
 
-        impl TryFrom<bytes::Bytes> for $t {
+        crate::local_macro::fixed_bytes!(@common $t);
+    };
+    (@convert $t:ident<$g:ident: $b:path>) => {
+        impl<$g: $b> TryFrom<&[u8]> for $t<$g> {
+            type Error = crate::ExtendError;
+
+            fn try_from(src: &[u8]) -> Result<Self, Self::Error> {
+                Ok(Self(
+                    src.try_into()
+                        .map_err(|_| crate::ExtendError::WrongLength)?,
+                    core::marker::PhantomData,
+                ))
+            }
+        }
+
+        crate::local_macro::fixed_bytes!(@common $t<$g: $b>);
+    };
+    (@common $t:ident $(<$g:ident: $b:path>)?) => {
+        impl$(<$g: $b>)? TryFrom<bytes::Bytes> for $t$(<$g>)? {
             type Error = crate::ExtendError;
 
             fn try_from(src: bytes::Bytes) -> Result<Self, Self::Error> {
@@ -19,17 +37,55 @@ macro_rules! fixed_bytes {
             }
         }
 
-        impl FixedBytes for $t {
+        impl$(<$g: $b>)? AsRef<[u8]> for $t$(<$g>)? {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+    };
+    ($t:ident $(<$g:ident: $b:path>)?) => {
+        crate::local_macro::fixed_bytes!(@convert $t $(<$g: $b>)?);
+
+        impl$(<$g: $b>)? FixedBytes for $t$(<$g>)? {
             fn copy_bytes(&self) -> bytes::Bytes {
                 bytes::Bytes::copy_from_slice(self.as_ref())
             }
         }
+    };
+}
+pub(crate) use fixed_bytes;
 
-        impl AsRef<[u8]> for $t {
-            fn as_ref(&self) -> &[u8] {
-                &self.0
+/// `fixed_bytes!` for secret material, which is wiped on drop, redacted in `Debug`
+/// and compared in constant time. `FixedBytes` is not implemented to avoid copies into `Bytes`.
+macro_rules! secret_bytes {
+    ($t:ident $(<$g:ident: $b:path>)?) => {
+        crate::local_macro::fixed_bytes!(@convert $t $(<$g: $b>)?);
+
+        impl$(<$g: $b>)? Drop for $t$(<$g>)? {
+            fn drop(&mut self) {
+                zeroize::Zeroize::zeroize(&mut self.0);
+            }
+        }
+
+        impl$(<$g: $b>)? core::fmt::Debug for $t$(<$g>)? {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str(concat!(stringify!($t), "(..)"))
             }
         }
+
+        impl$(<$g: $b>)? subtle::ConstantTimeEq for $t$(<$g>)? {
+            fn ct_eq(&self, other: &Self) -> subtle::Choice {
+                self.0.ct_eq(&other.0)
+            }
+        }
+
+        impl$(<$g: $b>)? PartialEq for $t$(<$g>)? {
+            fn eq(&self, other: &Self) -> bool {
+                subtle::ConstantTimeEq::ct_eq(self, other).into()
+            }
+        }
+
+        impl$(<$g: $b>)? Eq for $t$(<$g>)? {}
     };
 }
-pub(crate) use fixed_bytes;
+pub(crate) use secret_bytes;
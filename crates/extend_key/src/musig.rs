@@ -0,0 +1,801 @@
+use crate::base58::Prefix;
+use crate::ecdh::random_bytes;
+use crate::ecdsa_key::{PrvKey, PrvKeyBytes, PubKeyBytes, KEY_SIZE};
+use crate::extkey::ExtKey;
+use crate::fixed_bytes::FixedBytes;
+use crate::local_macro::fixed_bytes;
+use crate::schnorr::{
+    reduce, tagged_hash, tap_tweak_scalar, to_scalar, x_bytes, Parity, SchnorrSignature,
+    XOnlyPubKey,
+};
+use crate::ExtendError;
+use core::fmt;
+use elliptic_curve::ff::PrimeField;
+use elliptic_curve::group::Group;
+use elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use hdpath::node::Node;
+use hdpath::path::HDPath;
+use k256::{AffinePoint, EncodedPoint, ProjectivePoint, Scalar};
+use zeroize::{Zeroize, Zeroizing};
+
+const POINT_SIZE: usize = KEY_SIZE + 1;
+pub const PUB_NONCE_SIZE: usize = POINT_SIZE * 2;
+const SEC_NONCE_SIZE: usize = KEY_SIZE * 2 + POINT_SIZE;
+
+/// BIP-0328 chain code of the aggregate xpub, SHA-256 of `MuSig2MuSig2MuSig2`.
+const BIP328_CHAIN_CODE: [u8; 32] = [
+    0x86, 0x80, 0x87, 0xca, 0x02, 0xa6, 0xf9, 0x74, 0xc4, 0x59, 0x89, 0x24, 0xc3, 0x6b, 0x57, 0x76,
+    0x2d, 0x32, 0xcb, 0x45, 0x71, 0x71, 0x67, 0xe3, 0x00, 0x62, 0x2c, 0x71, 0x67, 0xe3, 0x89, 0x65,
+];
+
+/// Public nonce `cbytes(R1) || cbytes(R2)` of a signer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PubNonce([u8; PUB_NONCE_SIZE]);
+fixed_bytes!(PubNonce);
+
+/// Sum of the public nonces, where the point at infinity is 33 zero bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AggNonce([u8; PUB_NONCE_SIZE]);
+fixed_bytes!(AggNonce);
+
+/// Secret nonce `k1 || k2 || pk`. Neither `Clone` nor convertible from or to bytes,
+/// since signing consumes it to avoid reuse.
+pub struct SecNonce([u8; SEC_NONCE_SIZE]);
+
+impl Drop for SecNonce {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for SecNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecNonce(..)")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PartialSignature([u8; KEY_SIZE]);
+fixed_bytes!(PartialSignature);
+
+fn cpoint(bs: &[u8]) -> Result<AffinePoint, ExtendError> {
+    if bs.len() != POINT_SIZE {
+        return Err(ExtendError::WrongLength);
+    }
+    let encoded = EncodedPoint::from_bytes(bs).map_err(|_| ExtendError::InvalidPublicKey)?;
+    let p: Option<AffinePoint> = AffinePoint::from_encoded_point(&encoded).into();
+    p.ok_or(ExtendError::InvalidPublicKey)
+}
+
+/// `cpoint` which takes 33 zero bytes as the point at infinity.
+fn cpoint_ext(bs: &[u8]) -> Result<ProjectivePoint, ExtendError> {
+    if bs == [0; POINT_SIZE] {
+        Ok(ProjectivePoint::IDENTITY)
+    } else {
+        Ok(cpoint(bs)?.into())
+    }
+}
+
+fn cbytes_ext(p: &ProjectivePoint) -> [u8; POINT_SIZE] {
+    if bool::from(p.is_identity()) {
+        return [0; POINT_SIZE];
+    }
+    p.to_affine()
+        .to_encoded_point(true)
+        .as_bytes()
+        .try_into()
+        .expect("compressed point must have 33 bytes")
+}
+
+/// -1 if the y of the point is odd, 1 otherwise.
+fn sign_of(p: &AffinePoint) -> Scalar {
+    match Parity::of(p) {
+        Parity::Even => Scalar::ONE,
+        Parity::Odd => -Scalar::ONE,
+    }
+}
+
+//----------------------------------------------------------------
+
+/// BIP-0327 key aggregation with the accumulated tweaks, and the BIP-0328 xpub of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyAggContext {
+    keys: Vec<PubKeyBytes>,
+    list_hash: [u8; 32],
+    second: Option<PubKeyBytes>,
+    q: AffinePoint,
+    gacc: Scalar,
+    tacc: Scalar,
+    xpub: ExtKey<PubKeyBytes>,
+}
+
+impl KeyAggContext {
+    /// Aggregates the keys in the given order, see `key_sort` for the canonical one.
+    pub fn new(keys: &[PubKeyBytes]) -> Result<Self, ExtendError> {
+        let first = keys
+            .first()
+            .ok_or(ExtendError::InvalidFormat("MuSig2 keys"))?;
+        let list: Vec<&[u8]> = keys.iter().map(|k| k.as_ref()).collect();
+        let list_hash = tagged_hash("KeyAgg list", &list);
+        let second = keys.iter().find(|k| *k != first).cloned();
+        let mut ctx = Self {
+            keys: keys.to_vec(),
+            list_hash,
+            second,
+            q: AffinePoint::IDENTITY,
+            gacc: Scalar::ONE,
+            tacc: Scalar::ZERO,
+            xpub: ExtKey {
+                prefix: Prefix::XPUB,
+                parent: [0, 0, 0, 0].as_ref().try_into()?,
+                chain_code: BIP328_CHAIN_CODE.as_ref().try_into()?,
+                key: first.clone(),
+                depth: [0].as_ref().try_into()?,
+                child_number: 0.into(),
+            },
+        };
+        let q = keys.iter().try_fold(ProjectivePoint::IDENTITY, |acc, k| {
+            Ok::<_, ExtendError>(
+                acc + ProjectivePoint::from(cpoint(k.as_ref())?) * ctx.coefficient(k),
+            )
+        })?;
+        if bool::from(q.is_identity()) {
+            return Err(ExtendError::InvalidPublicKey);
+        }
+        ctx.q = q.to_affine();
+        ctx.xpub.key = ctx.aggregate_key()?;
+        Ok(ctx)
+    }
+
+    /// Sorts the keys lexicographically as `KeySort`.
+    pub fn key_sort(keys: &[PubKeyBytes]) -> Vec<PubKeyBytes> {
+        let mut sorted = keys.to_vec();
+        sorted.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        sorted
+    }
+
+    pub fn keys(&self) -> &[PubKeyBytes] {
+        &self.keys
+    }
+
+    fn coefficient(&self, pk: &PubKeyBytes) -> Scalar {
+        if Some(pk) == self.second.as_ref() {
+            Scalar::ONE
+        } else {
+            reduce(tagged_hash(
+                "KeyAgg coefficient",
+                &[&self.list_hash, pk.as_ref()],
+            ))
+        }
+    }
+
+    fn coefficient_of(&self, pk: &PubKeyBytes) -> Result<Scalar, ExtendError> {
+        if !self.keys.contains(pk) {
+            return Err(ExtendError::NotASigner);
+        }
+        Ok(self.coefficient(pk))
+    }
+
+    /// The aggregate key with the tweaks applied so far.
+    pub fn aggregate_key(&self) -> Result<PubKeyBytes, ExtendError> {
+        self.q.to_encoded_point(true).as_bytes().try_into()
+    }
+
+    pub fn x_only(&self) -> Result<XOnlyPubKey, ExtendError> {
+        x_bytes(&self.q).as_ref().try_into()
+    }
+
+    /// BIP-0328 xpub of the aggregate key, following `derive` but not the other tweaks.
+    pub fn xpub(&self) -> &ExtKey<PubKeyBytes> {
+        &self.xpub
+    }
+
+    /// Applies a plain (`x_only` false) or x-only tweak as `ApplyTweak`.
+    pub fn tweak(&self, tweak: &[u8; 32], x_only: bool) -> Result<Self, ExtendError> {
+        let g = if x_only {
+            sign_of(&self.q)
+        } else {
+            Scalar::ONE
+        };
+        let t = to_scalar(tweak).ok_or(ExtendError::InvalidFormat("MuSig2 tweak"))?;
+        let q = ProjectivePoint::from(self.q) * g + ProjectivePoint::GENERATOR * t;
+        if bool::from(q.is_identity()) {
+            return Err(ExtendError::InvalidFormat("MuSig2 tweak"));
+        }
+        Ok(Self {
+            q: q.to_affine(),
+            gacc: g * self.gacc,
+            tacc: t + g * self.tacc,
+            ..self.clone()
+        })
+    }
+
+    /// BIP-0341 taproot tweak of the aggregate key as the internal key.
+    pub fn tap_tweak(&self, merkle_root: Option<&[u8; 32]>) -> Result<Self, ExtendError> {
+        let t = tap_tweak_scalar(&self.x_only()?, merkle_root)?;
+        self.tweak(&t.to_repr().into(), true)
+    }
+
+    /// BIP-0328 normal child of the aggregate xpub, as a plain tweak of the aggregate key.
+    /// Must be done before the other tweaks, so use `derive` for a path.
+    pub fn get_child(&self, node: Node) -> Result<Self, ExtendError> {
+        self.derive_nodes(&[node])
+    }
+
+    pub fn derive(&self, path: &HDPath) -> Result<Self, ExtendError> {
+        self.derive_nodes(path.nodes())
+    }
+
+    /// Sums the tweaks along the nodes, then applies them to the untweaked key at once.
+    fn derive_nodes(&self, nodes: &[Node]) -> Result<Self, ExtendError> {
+        if !bool::from(self.tacc.is_zero()) || self.xpub.key != self.aggregate_key()? {
+            return Err(ExtendError::AlreadyTweaked);
+        }
+        let mut xpub = self.xpub.clone();
+        let mut tweak = Scalar::ZERO;
+        for node in nodes {
+            let child = xpub.get_child_normal_only(*node)?;
+            let hashed =
+                Zeroizing::new(xpub.hmac_child(xpub.key.as_ref(), &node.raw_index().into())?);
+            tweak += to_scalar(&hashed[..KEY_SIZE]).ok_or(ExtendError::InvalidChild(*node))?;
+            xpub = child;
+        }
+        Ok(Self {
+            xpub,
+            ..self.tweak(&tweak.to_repr().into(), false)?
+        })
+    }
+}
+
+//----------------------------------------------------------------
+
+impl SecNonce {
+    /// `NonceGen` with fresh random bytes. The optional inputs make the nonce robust
+    /// even if the random bytes are not, so they should be given when known.
+    pub fn generate(
+        pk: &PubKeyBytes,
+        sk: Option<&PrvKeyBytes>,
+        aggpk: Option<&XOnlyPubKey>,
+        msg: Option<&[u8]>,
+        extra_in: Option<&[u8]>,
+    ) -> Result<(Self, PubNonce), ExtendError> {
+        let rand = Zeroizing::new(random_bytes::<32>()?);
+        Self::generate_with(&rand, pk, sk, aggpk, msg, extra_in)
+    }
+
+    /// `generate` with the given random bytes, which must never be reused.
+    pub fn generate_with(
+        rand: &[u8; 32],
+        pk: &PubKeyBytes,
+        sk: Option<&PrvKeyBytes>,
+        aggpk: Option<&XOnlyPubKey>,
+        msg: Option<&[u8]>,
+        extra_in: Option<&[u8]>,
+    ) -> Result<(Self, PubNonce), ExtendError> {
+        let mut rand = Zeroizing::new(*rand);
+        if let Some(sk) = sk {
+            let aux = tagged_hash("MuSig/aux", &[rand.as_ref()]);
+            rand.iter_mut()
+                .zip(sk.as_ref().iter().zip(aux))
+                .for_each(|(r, (s, a))| *r = s ^ a);
+        }
+        let aggpk: &[u8] = aggpk.map(|k| k.as_ref()).unwrap_or_default();
+        let msg_prefixed = match msg {
+            None => vec![0],
+            Some(m) => [&[1], (m.len() as u64).to_be_bytes().as_ref(), m].concat(),
+        };
+        let extra_in = extra_in.unwrap_or_default();
+        let k = |i: u8| {
+            let hash = Zeroizing::new(tagged_hash(
+                "MuSig/nonce",
+                &[
+                    rand.as_ref(),
+                    &[pk.as_ref().len() as u8],
+                    pk.as_ref(),
+                    &[aggpk.len() as u8],
+                    aggpk,
+                    &msg_prefixed,
+                    &(extra_in.len() as u32).to_be_bytes(),
+                    extra_in,
+                    &[i],
+                ],
+            ));
+            Some(reduce(*hash)).filter(|k| !bool::from(k.is_zero()))
+        };
+        let (k1, k2) = k(0)
+            .zip(k(1))
+            .ok_or(ExtendError::InvalidFormat("MuSig2 nonce"))?;
+        let mut secnonce = Self([0; SEC_NONCE_SIZE]);
+        secnonce.0[..KEY_SIZE].copy_from_slice(&k1.to_repr());
+        secnonce.0[KEY_SIZE..KEY_SIZE * 2].copy_from_slice(&k2.to_repr());
+        secnonce.0[KEY_SIZE * 2..].copy_from_slice(pk.as_ref());
+        let r1 = cbytes_ext(&(ProjectivePoint::GENERATOR * k1));
+        let r2 = cbytes_ext(&(ProjectivePoint::GENERATOR * k2));
+        let pubnonce = [r1, r2].concat().as_slice().try_into()?;
+        Ok((secnonce, pubnonce))
+    }
+}
+
+impl AggNonce {
+    /// `NonceAgg` of the public nonces of all signers.
+    pub fn aggregate(pubnonces: &[PubNonce]) -> Result<Self, ExtendError> {
+        let sum = |j: usize| {
+            pubnonces
+                .iter()
+                .try_fold(ProjectivePoint::IDENTITY, |acc, n| {
+                    let p = cpoint(&n.0[j * POINT_SIZE..(j + 1) * POINT_SIZE])?;
+                    Ok::<_, ExtendError>(acc + ProjectivePoint::from(p))
+                })
+        };
+        [cbytes_ext(&sum(0)?), cbytes_ext(&sum(1)?)]
+            .concat()
+            .as_slice()
+            .try_into()
+    }
+}
+
+//----------------------------------------------------------------
+
+/// Signing session of a message with the aggregate nonce.
+#[derive(Debug, Clone)]
+pub struct Session {
+    ctx: KeyAggContext,
+    b: Scalar,
+    r: AffinePoint,
+    e: Scalar,
+}
+
+impl Session {
+    pub fn new(ctx: &KeyAggContext, aggnonce: &AggNonce, msg: &[u8]) -> Result<Self, ExtendError> {
+        let qx = x_bytes(&ctx.q);
+        let b = reduce(tagged_hash("MuSig/noncecoef", &[&aggnonce.0, &qx, msg]));
+        let r1 = cpoint_ext(&aggnonce.0[..POINT_SIZE])?;
+        let r2 = cpoint_ext(&aggnonce.0[POINT_SIZE..])?;
+        let r = r1 + r2 * b;
+        let r = if bool::from(r.is_identity()) {
+            AffinePoint::GENERATOR
+        } else {
+            r.to_affine()
+        };
+        let e = reduce(tagged_hash("BIP0340/challenge", &[&x_bytes(&r), &qx, msg]));
+        Ok(Self {
+            ctx: ctx.clone(),
+            b,
+            r,
+            e,
+        })
+    }
+
+    fn secret_scalar(bs: &[u8]) -> Option<Scalar> {
+        to_scalar(bs).filter(|k| !bool::from(k.is_zero()))
+    }
+
+    /// `Sign` with the secret nonce of this session, which is consumed.
+    pub fn sign(
+        &self,
+        secnonce: SecNonce,
+        sk: &PrvKeyBytes,
+    ) -> Result<PartialSignature, ExtendError> {
+        let k1 = Self::secret_scalar(&secnonce.0[..KEY_SIZE]);
+        let k2 = Self::secret_scalar(&secnonce.0[KEY_SIZE..KEY_SIZE * 2]);
+        let (k1, k2) = k1
+            .zip(k2)
+            .ok_or(ExtendError::InvalidFormat("MuSig2 nonce"))?;
+        let (k1, k2) = match Parity::of(&self.r) {
+            Parity::Even => (k1, k2),
+            Parity::Odd => (-k1, -k2),
+        };
+        let d = Self::secret_scalar(sk.as_ref()).ok_or(ExtendError::InvalidPrivateKey)?;
+        let pk = sk.get_public()?;
+        if pk.as_ref() != &secnonce.0[KEY_SIZE * 2..] {
+            return Err(ExtendError::TypeMismatched);
+        }
+        let a = self.ctx.coefficient_of(&pk)?;
+        let d = sign_of(&self.ctx.q) * self.ctx.gacc * d;
+        let s = k1 + self.b * k2 + self.e * a * d;
+        s.to_repr().as_slice().try_into()
+    }
+
+    /// `PartialSigVerify` of a signer by its public nonce and key.
+    pub fn verify(
+        &self,
+        psig: &PartialSignature,
+        pubnonce: &PubNonce,
+        pk: &PubKeyBytes,
+    ) -> Result<(), ExtendError> {
+        let s = to_scalar(&psig.0).ok_or(ExtendError::InvalidSignature)?;
+        let r1 = ProjectivePoint::from(cpoint(&pubnonce.0[..POINT_SIZE])?);
+        let r2 = ProjectivePoint::from(cpoint(&pubnonce.0[POINT_SIZE..])?);
+        let re = r1 + r2 * self.b;
+        let re = match Parity::of(&self.r) {
+            Parity::Even => re,
+            Parity::Odd => -re,
+        };
+        let a = self.ctx.coefficient_of(pk)?;
+        let g = sign_of(&self.ctx.q) * self.ctx.gacc;
+        let p = ProjectivePoint::from(cpoint(pk.as_ref())?);
+        if ProjectivePoint::GENERATOR * s != re + p * (self.e * a * g) {
+            return Err(ExtendError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// `PartialSigAgg` into the signature for `KeyAggContext::x_only`.
+    pub fn aggregate(&self, psigs: &[PartialSignature]) -> Result<SchnorrSignature, ExtendError> {
+        let s = psigs.iter().try_fold(Scalar::ZERO, |acc, psig| {
+            to_scalar(&psig.0)
+                .map(|s| acc + s)
+                .ok_or(ExtendError::InvalidSignature)
+        })?;
+        let s = s + self.e * sign_of(&self.ctx.q) * self.ctx.tacc;
+        [x_bytes(&self.r).as_ref(), s.to_repr().as_ref()]
+            .concat()
+            .as_slice()
+            .try_into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    fn pk(bs: &[u8]) -> PubKeyBytes {
+        bs.try_into().unwrap()
+    }
+
+    #[test]
+    fn key_agg_vectors() {
+        let x1 = pk(&hex!(
+            "02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"
+        ));
+        let x2 = pk(&hex!(
+            "03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659"
+        ));
+        let x3 = pk(&hex!(
+            "023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66"
+        ));
+        let cases = [
+            (
+                vec![x1.clone(), x2.clone(), x3.clone()],
+                hex!("90539EEDE565F5D054F32CC0C220126889ED1E5D193BAF15AEF344FE59D4610C"),
+            ),
+            (
+                vec![x3.clone(), x2.clone(), x1.clone()],
+                hex!("6204DE8B083426DC6EAF9502D27024D53FC826BF7D2012148A0575435DF54B2B"),
+            ),
+            (
+                vec![x1.clone(), x1.clone(), x1.clone()],
+                hex!("B436E3BAD62B8CD409969A224731C193D051162D8C5AE8B109306127DA3AA935"),
+            ),
+            (
+                vec![x1.clone(), x1.clone(), x2.clone(), x2.clone()],
+                hex!("69BC22BFA5D106306E48A20679DE1D7389386124D07571D0D872686028C26A3E"),
+            ),
+        ];
+        for (keys, expected) in cases {
+            let ctx = KeyAggContext::new(&keys).unwrap();
+            assert_eq!(ctx.x_only().unwrap().as_ref(), expected);
+        }
+        assert_eq!(
+            KeyAggContext::key_sort(&[x1.clone(), x2.clone(), x3.clone()]),
+            vec![x3, x1, x2]
+        );
+        assert!(KeyAggContext::new(&[]).is_err());
+    }
+
+    fn secnonce(bs: &[u8]) -> SecNonce {
+        SecNonce(bs.try_into().unwrap())
+    }
+
+    #[test]
+    fn nonce_gen_vectors() {
+        let sk: PrvKeyBytes = [2; KEY_SIZE].as_ref().try_into().unwrap();
+        let pk1 = pk(&hex!(
+            "024D4B6CD1361032CA9BD2AEB9D900AA4D45D9EAD80AC9423374C451A7254D0766"
+        ));
+        let aggpk: XOnlyPubKey = [7; 32].as_ref().try_into().unwrap();
+        let (sec, pubnonce) = SecNonce::generate_with(
+            &[0x0f; 32],
+            &pk1,
+            Some(&sk),
+            Some(&aggpk),
+            Some(&[1; 32]),
+            Some(&[8; 32]),
+        )
+        .unwrap();
+        assert_eq!(
+            sec.0,
+            hex!(
+                "B114E502BEAA4E301DD08A50264172C84E41650E6CB726B410C0694D59EFFB64"
+                "95B5CAF28D045B973D63E3C99A44B807BDE375FD6CB39E46DC4A511708D0E9D2"
+                "024D4B6CD1361032CA9BD2AEB9D900AA4D45D9EAD80AC9423374C451A7254D0766"
+            )
+        );
+        assert_eq!(
+            pubnonce.0,
+            hex!(
+                "02F7BE7089E8376EB355272368766B17E88E7DB72047D05E56AA881EA52B3B35DF"
+                "02C29C8046FDD0DED4C7E55869137200FBDBFE2EB654267B6D7013602CAED3115A"
+            )
+        );
+
+        let pk2 = pk(&hex!(
+            "02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"
+        ));
+        let (sec, pubnonce) =
+            SecNonce::generate_with(&[0x0f; 32], &pk2, None, None, None, None).unwrap();
+        assert_eq!(
+            sec.0,
+            hex!(
+                "89BDD787D0284E5E4D5FC572E49E316BAB7E21E3B1830DE37DFE80156FA41A6D"
+                "0B17AE8D024C53679699A6FD7944D9C4A366B514BAF43088E0708B1023DD2897"
+                "02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"
+            )
+        );
+        assert_eq!(
+            pubnonce.0,
+            hex!(
+                "02C96E7CB1E8AA5DAC64D872947914198F607D90ECDE5200DE52978AD5DED63C00"
+                "0299EC5117C2D29EDEE8A2092587C3909BE694D5CFF0667D6C02EA4059F7CD9786"
+            )
+        );
+    }
+
+    const SK: [u8; KEY_SIZE] =
+        hex!("7FB9E0E687ADA1EEBF7ECFE2F21E73EBDB51A7D450948DFE8D76D7F2D1007671");
+    const SEC_NONCE: [u8; SEC_NONCE_SIZE] = hex!(
+        "508B81A611F100A6B2B6B29656590898AF488BCF2E1F55CF22E5CFB84421FE61"
+        "FA27FD49B1D50085B481285E1CA205D55C82CC1B31FF5CD54A489829355901F7"
+        "03935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9"
+    );
+    const MSG: [u8; 32] = hex!("F95466D086770E689964664219266FE5ED215C92AE20BAB5C9D79ADDDDF3C0CF");
+
+    fn pubnonce(bs: &[u8]) -> PubNonce {
+        bs.try_into().unwrap()
+    }
+
+    #[test]
+    fn sign_verify_vectors() {
+        let sk: PrvKeyBytes = SK.as_ref().try_into().unwrap();
+        let keys = [
+            hex!("03935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9"),
+            hex!("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"),
+            hex!("02DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA661"),
+            hex!("020000000000000000000000000000000000000000000000000000000000000007"),
+        ];
+        let pubnonces = [
+            hex!(
+                "0337C87821AFD50A8644D820A8F3E02E499C931865C2360FB43D0A0D20DAFE07EA"
+                "0287BF891D2A6DEAEBADC909352AA9405D1428C15F4B75F04DAE642A95C2548480"
+            ),
+            hex!(
+                "0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"
+                "0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"
+            ),
+            hex!(
+                "032DE2662628C90B03F5E720284EB52FF7D71F4284F627B68A853D78C78E1FFE93"
+                "03E4C5524E83FFE1493B9077CF1CA6BEB2090C93D930321071AD40B2F44E599046"
+            ),
+            hex!(
+                "0237C87821AFD50A8644D820A8F3E02E499C931865C2360FB43D0A0D20DAFE07EA"
+                "0387BF891D2A6DEAEBADC909352AA9405D1428C15F4B75F04DAE642A95C2548480"
+            ),
+            hex!(
+                "020000000000000000000000000000000000000000000000000000000000000009"
+                "0287BF891D2A6DEAEBADC909352AA9405D1428C15F4B75F04DAE642A95C2548480"
+            ),
+        ];
+        let aggnonces = [
+            hex!(
+                "028465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61"
+                "037496A3CC86926D452CAFCFD55D25972CA1675D549310DE296BFF42F72EEEA8C9"
+            ),
+            [0; PUB_NONCE_SIZE],
+            hex!(
+                "048465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61"
+                "037496A3CC86926D452CAFCFD55D25972CA1675D549310DE296BFF42F72EEEA8C9"
+            ),
+            hex!(
+                "028465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61"
+                "020000000000000000000000000000000000000000000000000000000000000009"
+            ),
+            hex!(
+                "028465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61"
+                "02FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30"
+            ),
+        ];
+        let ctx = |indices: &[usize]| {
+            let keys: Vec<_> = indices.iter().map(|&i| pk(&keys[i])).collect();
+            KeyAggContext::new(&keys)
+        };
+        let session = |indices: &[usize], aggnonce: usize| {
+            let aggnonce = aggnonces[aggnonce].as_ref().try_into()?;
+            Session::new(&ctx(indices)?, &aggnonce, &MSG)
+        };
+
+        let valid = [
+            (
+                [0, 1, 2].as_ref(),
+                0,
+                hex!("012ABBCB52B3016AC03AD82395A1A415C48B93DEF78718E62A7A90052FE224FB"),
+            ),
+            (
+                &[1, 0, 2],
+                0,
+                hex!("9FF2F7AAA856150CC8819254218D3ADEEB0535269051897724F9DB3789513A52"),
+            ),
+            (
+                &[1, 2, 0],
+                0,
+                hex!("FA23C359F6FAC4E7796BB93BC9F0532A95468C539BA20FF86D7C76ED92227900"),
+            ),
+            (
+                // The aggregate nonce is the point at infinity
+                &[0, 1],
+                1,
+                hex!("AE386064B26105404798F75DE2EB9AF5EDA5387B064B83D049CB7C5E08879531"),
+            ),
+        ];
+        for (indices, aggnonce, expected) in valid {
+            let session = session(indices, aggnonce).unwrap();
+            let psig = session.sign(secnonce(&SEC_NONCE), &sk).unwrap();
+            assert_eq!(psig.0, expected);
+            session
+                .verify(&psig, &pubnonce(&pubnonces[0]), &pk(&keys[0]))
+                .unwrap();
+        }
+        let agg = AggNonce::aggregate(
+            &pubnonces[..3]
+                .iter()
+                .map(|n| pubnonce(n))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(agg.unwrap().0, aggnonces[0]);
+
+        // The signer is not included, or a key is invalid
+        let sign = |indices: &[usize], aggnonce: usize, secnonce: SecNonce| {
+            session(indices, aggnonce)?.sign(secnonce, &sk)
+        };
+        assert!(matches!(
+            sign(&[1, 2], 0, secnonce(&SEC_NONCE)),
+            Err(ExtendError::NotASigner)
+        ));
+        assert!(sign(&[1, 0, 3], 0, secnonce(&SEC_NONCE)).is_err());
+        // Invalid aggregate nonces
+        for aggnonce in 2..5 {
+            assert!(sign(&[1, 2, 0], aggnonce, secnonce(&SEC_NONCE)).is_err());
+        }
+        // The secret nonce is zero, as it is after being erased
+        let mut zero = SEC_NONCE;
+        zero[..KEY_SIZE * 2].fill(0);
+        assert!(sign(&[0, 1, 2], 0, secnonce(&zero)).is_err());
+
+        // Wrong signatures, a wrong signer and an overflowing signature
+        let session = session(&[0, 1, 2], 0).unwrap();
+        let verify = |psig: [u8; KEY_SIZE], signer: usize| {
+            session.verify(
+                &psig.as_ref().try_into().unwrap(),
+                &pubnonce(&pubnonces[signer]),
+                &pk(&keys[signer]),
+            )
+        };
+        let cases = [
+            (
+                hex!("FED54434AD4CFE953FC527DC6A5E5BE8F6234907B7C187559557CE87A0541C46"),
+                0,
+            ),
+            (
+                hex!("012ABBCB52B3016AC03AD82395A1A415C48B93DEF78718E62A7A90052FE224FB"),
+                1,
+            ),
+            (
+                hex!("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141"),
+                0,
+            ),
+        ];
+        for (psig, signer) in cases {
+            assert!(verify(psig, signer).is_err());
+        }
+        // Invalid public nonce and public key
+        let psig: PartialSignature =
+            hex!("012ABBCB52B3016AC03AD82395A1A415C48B93DEF78718E62A7A90052FE224FB")
+                .as_ref()
+                .try_into()
+                .unwrap();
+        assert!(session
+            .verify(&psig, &pubnonce(&pubnonces[4]), &pk(&keys[0]))
+            .is_err());
+        assert!(ctx(&[3, 1, 2]).is_err());
+    }
+
+    #[test]
+    fn tweak_vectors() {
+        let sk: PrvKeyBytes = SK.as_ref().try_into().unwrap();
+        let keys = [
+            hex!("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"),
+            hex!("02DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659"),
+            hex!("03935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9"),
+        ];
+        let ctx = KeyAggContext::new(&keys.map(|k| pk(&k))).unwrap();
+        let aggnonce: AggNonce = hex!(
+            "028465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61"
+            "037496A3CC86926D452CAFCFD55D25972CA1675D549310DE296BFF42F72EEEA8C9"
+        )
+        .as_ref()
+        .try_into()
+        .unwrap();
+        let signer_nonce = pubnonce(&hex!(
+            "0337C87821AFD50A8644D820A8F3E02E499C931865C2360FB43D0A0D20DAFE07EA"
+            "0287BF891D2A6DEAEBADC909352AA9405D1428C15F4B75F04DAE642A95C2548480"
+        ));
+        let tweaks = [
+            hex!("E8F791FF9225A2AF0102AFFF4A9A723D9612A682A25EBE79802B263CDFCD83BB"),
+            hex!("AE2EA797CC0FE72AC5B97B97F3C6957D7E4199A167A58EB08BCAFFDA70AC0455"),
+            hex!("F52ECBC565B3D8BEA2DFD5B75A4F457E54369809322E4120831626F290FA87E0"),
+            hex!("1969AD73CC177FA0B4FCED6DF1F7BF9907E665FDE9BA196A74FED0A3CF5AEF9D"),
+        ];
+        let cases = [
+            (
+                [true].as_ref(),
+                hex!("E28A5C66E61E178C2BA19DB77B6CF9F7E2F0F56C17918CD13135E60CC848FE91"),
+            ),
+            (
+                &[false],
+                hex!("38B0767798252F21BF5702C48028B095428320F73A4B14DB1E25DE58543D2D2D"),
+            ),
+            (
+                &[false, true],
+                hex!("408A0A21C4A0F5DACAF9646AD6EB6FECD7F7A11F03ED1F48DFFF2185BC2C2408"),
+            ),
+            (
+                &[false, false, true, true],
+                hex!("45ABD206E61E3DF2EC9E264A6FEC8292141A633C28586388235541F9ADE75435"),
+            ),
+            (
+                &[true, false, true, false],
+                hex!("B255FDCAC27B40C7CE7848E2D3B7BF5EA0ED756DA81565AC804CCCA3E1D5D239"),
+            ),
+        ];
+        for (x_only, expected) in cases {
+            let tweaked = tweaks
+                .iter()
+                .zip(x_only)
+                .try_fold(ctx.clone(), |ctx, (t, x_only)| ctx.tweak(t, *x_only))
+                .unwrap();
+            let session = Session::new(&tweaked, &aggnonce, &MSG).unwrap();
+            let psig = session.sign(secnonce(&SEC_NONCE), &sk).unwrap();
+            assert_eq!(psig.0, expected);
+            session.verify(&psig, &signer_nonce, &pk(&keys[2])).unwrap();
+        }
+
+        // The tweak is not less than the order
+        let n = hex!("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+        assert!(ctx.tweak(&n, false).is_err());
+    }
+
+    #[test]
+    fn nonce_agg() {
+        let key = |i: u8| -> PrvKeyBytes { [i; KEY_SIZE].as_ref().try_into().unwrap() };
+        let (pk1, pk2) = (key(1).get_public().unwrap(), key(2).get_public().unwrap());
+        let (_, n1) = SecNonce::generate_with(&[0; 32], &pk1, None, None, None, None).unwrap();
+        let (_, n2) = SecNonce::generate_with(&[0; 32], &pk2, None, None, None, None).unwrap();
+        assert_ne!(n1, n2);
+        let agg = AggNonce::aggregate(&[n1.clone(), n2]).unwrap();
+        assert_eq!(
+            AggNonce::aggregate(core::slice::from_ref(&n1)).unwrap().0,
+            n1.0
+        );
+        assert_ne!(agg.0, n1.0);
+
+        // R + (-R) is the point at infinity
+        let mut neg = n1.0;
+        neg[0] ^= 1;
+        neg[POINT_SIZE] ^= 1;
+        let neg: PubNonce = neg.as_ref().try_into().unwrap();
+        assert_eq!(
+            AggNonce::aggregate(&[n1, neg]).unwrap().0,
+            [0; PUB_NONCE_SIZE]
+        );
+    }
+}
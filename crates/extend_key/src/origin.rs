@@ -0,0 +1,302 @@
+use crate::base58;
+use crate::ecdsa_key::{Base58Key, Fingerprint, KeyBytes, PrvKey, PubKey};
+use crate::extkey::ExtKey;
+use crate::ExtendError;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use core::fmt;
+use core::str::FromStr;
+use hdpath::node::Node;
+use hdpath::path::HDPath;
+
+const FINGERPRINT_SIZE: usize = 4;
+const INDEX_SIZE: usize = 4;
+
+/// Master key fingerprint and the path derived from it.
+/// `path` is `None` for the master key itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyOrigin {
+    pub fingerprint: Fingerprint,
+    pub path: Option<HDPath>,
+}
+
+impl KeyOrigin {
+    pub fn new(fingerprint: Fingerprint, path: Option<HDPath>) -> Self {
+        Self { fingerprint, path }
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        self.path.as_ref().map(|p| p.nodes()).unwrap_or_default()
+    }
+
+    pub fn raw_indices(&self) -> Vec<u32> {
+        self.nodes().iter().map(Node::raw_index).collect()
+    }
+
+    pub fn from_raw_indices(fingerprint: Fingerprint, indices: &[u32]) -> Self {
+        let nodes: Vec<Node> = indices.iter().map(|i| Node::from(*i)).collect();
+        Self::new(fingerprint, nodes.try_into().ok())
+    }
+
+    /// Fingerprint followed by little-endian child indices as used in PSBT.
+    pub fn encode(&self) -> Bytes {
+        let nodes = self.nodes();
+        let mut buf = BytesMut::with_capacity(FINGERPRINT_SIZE + nodes.len() * INDEX_SIZE);
+        buf.put(self.fingerprint.as_ref());
+        nodes
+            .iter()
+            .for_each(|node| buf.put_u32_le(node.raw_index()));
+        buf.freeze()
+    }
+
+    pub fn decode(src: &[u8]) -> Result<Self, ExtendError> {
+        if src.len() < FINGERPRINT_SIZE
+            || !(src.len() - FINGERPRINT_SIZE).is_multiple_of(INDEX_SIZE)
+        {
+            return Err(ExtendError::WrongLength);
+        }
+        let mut buf = src;
+        let fingerprint = buf[..FINGERPRINT_SIZE].try_into()?;
+        buf.advance(FINGERPRINT_SIZE);
+
+        let mut indices = Vec::with_capacity(buf.len() / INDEX_SIZE);
+        while buf.has_remaining() {
+            indices.push(buf.get_u32_le());
+        }
+        Ok(Self::from_raw_indices(fingerprint, &indices))
+    }
+}
+
+impl KeyOrigin {
+    /// Origin of the child at `path` from the key of this origin.
+    pub fn extend(&self, path: &[Node]) -> Self {
+        let nodes = [self.nodes(), path].concat();
+        Self::new(self.fingerprint.clone(), nodes.try_into().ok())
+    }
+}
+
+impl From<&KeyOrigin> for Bytes {
+    fn from(src: &KeyOrigin) -> Self {
+        src.encode()
+    }
+}
+
+impl TryFrom<&[u8]> for KeyOrigin {
+    type Error = ExtendError;
+
+    fn try_from(src: &[u8]) -> Result<Self, Self::Error> {
+        Self::decode(src)
+    }
+}
+
+impl fmt::Display for KeyOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}", self.fingerprint)?;
+        self.nodes()
+            .iter()
+            .try_for_each(|node| write!(f, "/{node}"))?;
+        f.write_str("]")
+    }
+}
+
+impl FromStr for KeyOrigin {
+    type Err = ExtendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let inner = s
+            .strip_prefix('[')
+            .and_then(|a| a.strip_suffix(']'))
+            .ok_or(ExtendError::InvalidFormat("KeyOrigin"))?;
+        let (fp, path) = match inner.split_once('/') {
+            Some((fp, rest)) => (fp, Some(format!("m/{rest}").parse()?)),
+            None => (inner, None),
+        };
+        Ok(Self::new(fp.parse()?, path))
+    }
+}
+
+//----------------------------------------------------------------
+
+/// Extended key with its origin, which follows every derivation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OriginKey<A> {
+    pub origin: KeyOrigin,
+    pub key: ExtKey<A>,
+}
+
+impl<A: KeyBytes> OriginKey<A> {
+    /// `origin` must have as many nodes as the depth of `key`, ending with its child number.
+    pub fn new(origin: KeyOrigin, key: ExtKey<A>) -> Result<Self, ExtendError> {
+        let nodes = origin.nodes();
+        let consistent = nodes.len() == usize::from(key.depth.as_ref()[0])
+            && nodes
+                .last()
+                .is_none_or(|node| *node == Node::from(&key.child_number));
+        if !consistent {
+            return Err(ExtendError::InvalidHDPath(None));
+        }
+        Ok(Self { origin, key })
+    }
+
+    fn child(&self, node: Node, key: ExtKey<A>) -> Self {
+        Self {
+            origin: self.origin.extend(&[node]),
+            key,
+        }
+    }
+}
+
+impl<A, B> OriginKey<A>
+where
+    A: PrvKey<Public = B>,
+    B: PubKey,
+{
+    /// `key` is taken as the master key.
+    pub fn master(key: ExtKey<A>) -> Result<Self, ExtendError> {
+        let fingerprint = key.key.get_public()?.fingerprint();
+        Self::new(KeyOrigin::new(fingerprint, None), key)
+    }
+
+    pub fn get_child(&self, node: Node) -> Result<Self, ExtendError> {
+        Ok(self.child(node, self.key.get_child(node)?))
+    }
+
+    pub fn derive_child(&self, path: HDPath) -> Result<Self, ExtendError> {
+        let origin = self.origin.extend(path.nodes());
+        Ok(Self {
+            origin,
+            key: self.key.derive_child(path)?,
+        })
+    }
+
+    pub fn get_public(&self) -> Result<OriginKey<B>, ExtendError> {
+        Ok(OriginKey {
+            origin: self.origin.clone(),
+            key: self.key.get_public()?,
+        })
+    }
+}
+
+impl<A: PubKey> OriginKey<A> {
+    pub fn get_child_normal_only(&self, node: Node) -> Result<Self, ExtendError> {
+        Ok(self.child(node, self.key.get_child_normal_only(node)?))
+    }
+}
+
+/// Key expression of output descriptors, as `[d34db33f/84'/0'/0']xpub...`.
+impl<A: Base58Key> fmt::Display for OriginKey<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.origin, self.key)
+    }
+}
+
+impl<A> FromStr for OriginKey<A>
+where
+    A: Base58Key,
+    A: for<'a> TryFrom<&'a [u8], Error = ExtendError>,
+{
+    type Err = ExtendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (origin, key) = s
+            .split_once(']')
+            .ok_or(ExtendError::InvalidFormat("OriginKey"))?;
+        let origin = format!("{origin}]").parse()?;
+        Self::new(origin, base58::decode(key)?.try_into()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::base58::Prefix;
+    use crate::ecdsa_key::{PrvKeyBytes, PubKeyBytes};
+    use hex_literal::hex;
+
+    #[test]
+    fn parse_str() {
+        let origin: KeyOrigin = "[d34db33f/84'/0'/0']".parse().unwrap();
+        assert_eq!(
+            origin.fingerprint,
+            hex!("d34db33f").as_ref().try_into().unwrap()
+        );
+        assert_eq!(origin.path, Some("m/84'/0'/0'".parse().unwrap()));
+        assert_eq!("[d34db33f/84'/0'/0']", origin.to_string());
+
+        let master: KeyOrigin = "[D34DB33F]".parse().unwrap();
+        assert_eq!(master.path, None);
+        assert_eq!("[d34db33f]", master.to_string());
+
+        [
+            "",
+            "[]",
+            "d34db33f/84'",
+            "[d34db33f/84'",
+            "[d34db3/84']",
+            "[d34db33f/]",
+            "[d34db33f//0]",
+            "[zzzzzzzz/0]",
+            "[+d4db33f/0]",
+        ]
+        .into_iter()
+        .for_each(|src| {
+            let r: Result<KeyOrigin, _> = src.parse();
+            assert!(r.is_err(), "{src}");
+        });
+    }
+
+    #[test]
+    fn binary() {
+        let origin: KeyOrigin = "[d34db33f/44'/0'/0'/1/2]".parse().unwrap();
+        let encoded = origin.encode();
+        assert_eq!(
+            encoded.as_ref(),
+            hex!("d34db33f 2c000080 00000080 00000080 01000000 02000000")
+        );
+        assert_eq!(origin, KeyOrigin::decode(&encoded).unwrap());
+
+        let master = KeyOrigin::decode(&hex!("d34db33f")).unwrap();
+        assert_eq!(master.path, None);
+        assert_eq!(master.encode().as_ref(), hex!("d34db33f"));
+
+        assert!(KeyOrigin::decode(&hex!("d34db3")).is_err());
+        assert!(KeyOrigin::decode(&hex!("d34db33f 2c0000")).is_err());
+    }
+
+    #[test]
+    fn raw_indices() {
+        let origin: KeyOrigin = "[d34db33f/84'/0'/5]".parse().unwrap();
+        assert_eq!(origin.raw_indices(), vec![0x80000054, 0x80000000, 5]);
+        assert_eq!(
+            origin,
+            KeyOrigin::from_raw_indices(origin.fingerprint.clone(), &origin.raw_indices())
+        );
+    }
+
+    #[test]
+    fn origin_key() {
+        let m: ExtKey<PrvKeyBytes> =
+            ExtKey::from_seed(Prefix::XPRV, &hex!("000102030405060708090a0b0c0d0e0f")).unwrap();
+        let master = OriginKey::master(m.clone()).unwrap();
+        assert_eq!(master.to_string(), format!("[3442193e]{m}"));
+
+        let path: HDPath = "m/84'/0'/0'/0/5".parse().unwrap();
+        let child = master.derive_child(path.clone()).unwrap();
+        assert_eq!(child.key, m.derive_child(path.clone()).unwrap());
+        assert_eq!(child.origin.to_string(), "[3442193e/84'/0'/0'/0/5]");
+
+        let account = master
+            .derive_child("m/84'/0'/0'".parse().unwrap())
+            .unwrap()
+            .get_public()
+            .unwrap();
+        let from_public = account
+            .get_child_normal_only(Node::Normal(0))
+            .and_then(|k| k.get_child_normal_only(Node::Normal(5)))
+            .unwrap();
+        assert_eq!(from_public, child.get_public().unwrap());
+
+        let parsed: OriginKey<PubKeyBytes> = account.to_string().parse().unwrap();
+        assert_eq!(parsed, account);
+        assert!(OriginKey::new(master.origin.clone(), account.key).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use crate::curve::Secp256k1;
+use crate::ecdsa_key::{PrvKey, PrvKeyBytes, PubKeyBytes, KEY_SIZE};
+use crate::fixed_bytes::FixedBytes;
+use crate::local_macro::fixed_bytes;
+use crate::ExtendError;
+use elliptic_curve::ff::PrimeField;
+use elliptic_curve::group::Group;
+use elliptic_curve::ops::Reduce;
+use elliptic_curve::sec1::{FromEncodedPoint, Tag, ToEncodedPoint};
+use k256::schnorr::{Signature, SigningKey, VerifyingKey};
+use k256::{AffinePoint, EncodedPoint, FieldBytes, ProjectivePoint, Scalar, U256};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+pub const SCHNORR_SIGNATURE_SIZE: usize = KEY_SIZE * 2;
+
+/// BIP-0340 public key, the x coordinate of the point with even y.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct XOnlyPubKey([u8; KEY_SIZE]);
+fixed_bytes!(XOnlyPubKey);
+
+/// BIP-0340 signature `bytes(R) || bytes(s)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SchnorrSignature([u8; SCHNORR_SIGNATURE_SIZE]);
+fixed_bytes!(SchnorrSignature);
+
+/// Parity of the y coordinate dropped from an x-only key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+impl Parity {
+    pub(crate) fn of(point: &AffinePoint) -> Self {
+        if point.to_encoded_point(true).tag() == Tag::CompressedOddY {
+            Parity::Odd
+        } else {
+            Parity::Even
+        }
+    }
+}
+
+pub(crate) fn tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag = Sha256::digest(tag.as_bytes());
+    let mut hash = Sha256::new();
+    hash.update(tag);
+    hash.update(tag);
+    data.iter().for_each(|d| hash.update(d));
+    hash.finalize().into()
+}
+
+pub(crate) fn reduce(bs: [u8; 32]) -> Scalar {
+    <Scalar as Reduce<U256>>::reduce_bytes(&bs.into())
+}
+
+pub(crate) fn to_scalar(bs: &[u8]) -> Option<Scalar> {
+    Scalar::from_repr(*FieldBytes::from_slice(bs)).into()
+}
+
+pub(crate) fn x_bytes(point: &AffinePoint) -> [u8; KEY_SIZE] {
+    let encoded = point.to_encoded_point(true);
+    encoded.as_bytes()[1..]
+        .try_into()
+        .expect("compressed point must have 32 bytes of x")
+}
+
+/// The secret scalar whose point has even y, with the point. `k256::schnorr` has no
+/// tweaking, so the taproot tweak works on the scalar and point directly.
+fn even_secret(key: &PrvKeyBytes<Secp256k1>) -> Result<(Scalar, AffinePoint), ExtendError> {
+    let d = to_scalar(key.as_ref())
+        .filter(|d| !bool::from(d.is_zero()))
+        .ok_or(ExtendError::InvalidPrivateKey)?;
+    let p = (ProjectivePoint::GENERATOR * d).to_affine();
+    match Parity::of(&p) {
+        Parity::Even => Ok((d, p)),
+        Parity::Odd => Ok((-d, p)),
+    }
+}
+
+/// `TapTweak` hash of the internal key and the script tree, which must be below n.
+pub(crate) fn tap_tweak_scalar(
+    internal: &XOnlyPubKey,
+    merkle_root: Option<&[u8; 32]>,
+) -> Result<Scalar, ExtendError> {
+    let root: &[u8] = merkle_root.map(|r| r.as_ref()).unwrap_or_default();
+    let t = tagged_hash("TapTweak", &[internal.as_ref(), root]);
+    to_scalar(&t).ok_or(ExtendError::InvalidFormat("taproot tweak"))
+}
+
+//----------------------------------------------------------------
+
+impl XOnlyPubKey {
+    /// Whether the key is the x coordinate of a point on the curve.
+    pub fn validate(&self) -> Result<(), ExtendError> {
+        self.lift_x().map(|_| ())
+    }
+
+    fn lift_x(&self) -> Result<AffinePoint, ExtendError> {
+        let encoded = EncodedPoint::from_bytes([&[0x02], self.0.as_ref()].concat())
+            .map_err(|_| ExtendError::InvalidPublicKey)?;
+        let p: Option<AffinePoint> = AffinePoint::from_encoded_point(&encoded).into();
+        p.ok_or(ExtendError::InvalidPublicKey)
+    }
+
+    pub fn verify(&self, msg: &[u8], sig: &SchnorrSignature) -> Result<(), ExtendError> {
+        let key = VerifyingKey::from_bytes(&self.0).map_err(|_| ExtendError::InvalidPublicKey)?;
+        let sig = Signature::try_from(sig.as_ref()).map_err(|_| ExtendError::InvalidSignature)?;
+        key.verify_raw(msg, &sig)
+            .map_err(|_| ExtendError::InvalidSignature)
+    }
+
+    /// BIP-0341 output key `P + H_TapTweak(P || merkle_root) * G` and its parity.
+    /// `merkle_root` is `None` for a key path only output.
+    pub fn tap_tweak(
+        &self,
+        merkle_root: Option<&[u8; 32]>,
+    ) -> Result<(XOnlyPubKey, Parity), ExtendError> {
+        let t = tap_tweak_scalar(self, merkle_root)?;
+        let q = ProjectivePoint::from(self.lift_x()?) + ProjectivePoint::GENERATOR * t;
+        if bool::from(q.is_identity()) {
+            return Err(ExtendError::InvalidFormat("taproot tweak"));
+        }
+        let q = q.to_affine();
+        Ok((XOnlyPubKey(x_bytes(&q)), Parity::of(&q)))
+    }
+}
+
+impl PubKeyBytes<Secp256k1> {
+    pub fn x_only(&self) -> XOnlyPubKey {
+        XOnlyPubKey(
+            self.as_ref()[1..]
+                .try_into()
+                .expect("compressed key must have 32 bytes of x"),
+        )
+    }
+
+    pub fn parity(&self) -> Parity {
+        if self.as_ref()[0] == 0x03 {
+            Parity::Odd
+        } else {
+            Parity::Even
+        }
+    }
+}
+
+impl PrvKeyBytes<Secp256k1> {
+    pub fn x_only_public(&self) -> Result<XOnlyPubKey, ExtendError> {
+        Ok(self.get_public()?.x_only())
+    }
+
+    /// BIP-0340 signing with 32 bytes of auxiliary randomness.
+    pub fn sign_schnorr(
+        &self,
+        msg: &[u8],
+        aux_rand: &[u8; 32],
+    ) -> Result<SchnorrSignature, ExtendError> {
+        let key =
+            SigningKey::from_bytes(self.as_ref()).map_err(|_| ExtendError::InvalidPrivateKey)?;
+        let sig = key
+            .sign_raw(msg, aux_rand)
+            .map_err(|_| ExtendError::InvalidFormat("nonce"))?;
+        sig.to_bytes().as_slice().try_into()
+    }
+
+    /// BIP-0341 tweaked secret key for the key path spending.
+    pub fn tap_tweak(&self, merkle_root: Option<&[u8; 32]>) -> Result<Self, ExtendError> {
+        let (d, p) = even_secret(self)?;
+        let t = tap_tweak_scalar(&XOnlyPubKey(x_bytes(&p)), merkle_root)?;
+        let tweaked = Zeroizing::new(d + t);
+        if bool::from(tweaked.is_zero()) {
+            return Err(ExtendError::InvalidFormat("taproot tweak"));
+        }
+        Zeroizing::new(tweaked.to_repr()).as_slice().try_into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    // https://github.com/bitcoin/bips/blob/master/bip-0341/wallet-test-vectors.json
+    #[test]
+    fn tap_tweak_public() {
+        [
+            (
+                hex!("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d"),
+                None,
+                hex!("53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343"),
+            ),
+            (
+                hex!("187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27"),
+                Some(hex!(
+                    "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"
+                )),
+                hex!("147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3"),
+            ),
+        ]
+        .into_iter()
+        .for_each(|(internal, root, expected)| {
+            let internal = XOnlyPubKey(internal);
+            let (tweaked, _) = internal.tap_tweak(root.as_ref()).unwrap();
+            assert_eq!(tweaked.as_ref(), expected);
+        });
+    }
+
+    #[test]
+    fn tap_tweak_private() {
+        let prv: PrvKeyBytes =
+            hex!("6b973d88838f27366ed61c9ad6367663045cb456e28335c109e30717ae0c6baa")
+                .as_ref()
+                .try_into()
+                .unwrap();
+        let root = hex!("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21");
+        [None, Some(&root)].into_iter().for_each(|root| {
+            let (expected, parity) = prv.x_only_public().unwrap().tap_tweak(root).unwrap();
+            let tweaked = prv.tap_tweak(root).unwrap().get_public().unwrap();
+            assert_eq!(tweaked.x_only(), expected);
+            assert_eq!(tweaked.parity(), parity);
+
+            let sig = prv
+                .tap_tweak(root)
+                .unwrap()
+                .sign_schnorr(b"", &[0; 32])
+                .unwrap();
+            assert!(expected.verify(b"", &sig).is_ok());
+        });
+    }
+}
@@ -0,0 +1,229 @@
+use crate::any_key::AnyExtKey;
+use crate::base58::{self, DecodedExtKey, Prefix};
+use crate::curve::EcdsaCurve;
+use crate::ecdsa_key::{Base58Key, Fingerprint, PubKeyBytes};
+use crate::extkey::ExtKey;
+use crate::hex;
+use crate::origin::KeyOrigin;
+use crate::ExtendError;
+use core::fmt;
+use core::marker::PhantomData;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+type ParseStr<T> = fn(&str) -> Result<T, ExtendError>;
+type ParseBytes<T> = fn(&[u8]) -> Result<T, ExtendError>;
+
+struct ExtendVisitor<T> {
+    expecting: &'static str,
+    parse_str: ParseStr<T>,
+    parse_bytes: ParseBytes<T>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> ExtendVisitor<T> {
+    fn new(expecting: &'static str, parse_str: ParseStr<T>, parse_bytes: ParseBytes<T>) -> Self {
+        Self {
+            expecting,
+            parse_str,
+            parse_bytes,
+            _marker: PhantomData,
+        }
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(self)
+        } else {
+            deserializer.deserialize_bytes(self)
+        }
+    }
+}
+
+impl<'de, T> de::Visitor<'de> for ExtendVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        (self.parse_str)(v).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        (self.parse_bytes)(v).map_err(E::custom)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bs = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(b) = seq.next_element()? {
+            bs.push(b);
+        }
+        self.visit_bytes(&bs)
+    }
+}
+
+fn serialize_hex<S: Serializer>(src: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex::encode(src))
+    } else {
+        serializer.serialize_bytes(src)
+    }
+}
+
+macro_rules! serde_hex {
+    ($t:ident) => {
+        impl Serialize for $t {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_hex(self.as_ref(), serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                ExtendVisitor::new(
+                    concat!("hex string or bytes of ", stringify!($t)),
+                    |s| hex::decode(s, stringify!($t))?.as_slice().try_into(),
+                    |bs| bs.try_into(),
+                )
+                .deserialize(deserializer)
+            }
+        }
+    };
+}
+
+serde_hex!(Prefix);
+serde_hex!(Fingerprint);
+serde_hex!(PubKeyBytes);
+
+//----------------------------------------------------------------
+
+impl<A: Base58Key> Serialize for ExtKey<A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let decoded: DecodedExtKey = self.into();
+        if serializer.is_human_readable() {
+            serializer.collect_str(&decoded)
+        } else {
+            serializer.serialize_bytes(&base58::encode_bytes(&decoded))
+        }
+    }
+}
+
+impl<'de, A> Deserialize<'de> for ExtKey<A>
+where
+    A: Base58Key,
+    A: for<'a> TryFrom<&'a [u8], Error = ExtendError>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ExtendVisitor::new(
+            "base58 extended key or its serialized bytes",
+            |s| base58::decode(s)?.try_into(),
+            |bs| base58::decode_bytes(bs)?.try_into(),
+        )
+        .deserialize(deserializer)
+    }
+}
+
+impl<C: EcdsaCurve> Serialize for AnyExtKey<C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Private(k) => k.serialize(serializer),
+            Self::Public(k) => k.serialize(serializer),
+        }
+    }
+}
+
+impl<'de, C: EcdsaCurve> Deserialize<'de> for AnyExtKey<C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ExtendVisitor::new(
+            "base58 extended key or its serialized bytes",
+            |s| s.parse(),
+            |bs| base58::decode_bytes(bs)?.try_into(),
+        )
+        .deserialize(deserializer)
+    }
+}
+
+impl Serialize for KeyOrigin {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(&self.encode())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyOrigin {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ExtendVisitor::new(
+            "key origin string or its serialized bytes",
+            |s| s.parse(),
+            KeyOrigin::decode,
+        )
+        .deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecdsa_key::PrvKeyBytes;
+    use hex_literal::hex;
+    use serde_test::{assert_tokens, Configure, Token};
+
+    const XPRV: &str = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+    const XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+
+    #[test]
+    fn hex_bytes() {
+        assert_tokens(&Prefix::XPUB.readable(), &[Token::Str("0488b21e")]);
+        assert_tokens(
+            &Prefix::XPUB.compact(),
+            &[Token::Bytes(&[0x04, 0x88, 0xb2, 0x1e])],
+        );
+
+        let fp: Fingerprint = "d34db33f".parse().unwrap();
+        assert_tokens(&fp.clone().readable(), &[Token::Str("d34db33f")]);
+        assert_tokens(&fp.compact(), &[Token::Bytes(&[0xd3, 0x4d, 0xb3, 0x3f])]);
+    }
+
+    #[test]
+    fn ext_key() {
+        let xprv: ExtKey<PrvKeyBytes> = base58::decode(XPRV).unwrap().try_into().unwrap();
+        assert_tokens(&xprv.clone().readable(), &[Token::Str(XPRV)]);
+
+        let xpub: ExtKey<PubKeyBytes> = xprv.get_public().unwrap();
+        assert_tokens(&xpub.clone().readable(), &[Token::Str(XPUB)]);
+
+        assert_tokens(
+            &xpub.compact(),
+            &[Token::Bytes(&hex!(
+                "0488b21e 00 00000000 00000000"
+                "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508"
+                "0339a36013301597daef41fbe593a02cc513d0b55527ec2df1050e2e8ff49c85c2"
+            ))],
+        );
+    }
+
+    #[test]
+    fn any_ext_key() {
+        let xprv: AnyExtKey = XPRV.parse().unwrap();
+        assert_tokens(&xprv.readable(), &[Token::Str(XPRV)]);
+        let xpub: AnyExtKey = XPUB.parse().unwrap();
+        assert_tokens(&xpub.readable(), &[Token::Str(XPUB)]);
+    }
+
+    #[test]
+    fn key_origin() {
+        let origin: KeyOrigin = "[d34db33f/84'/0'/0']".parse().unwrap();
+        assert_tokens(
+            &origin.clone().readable(),
+            &[Token::Str("[d34db33f/84'/0'/0']")],
+        );
+        assert_tokens(
+            &origin.compact(),
+            &[Token::Bytes(&hex!("d34db33f 54000080 00000080 00000080"))],
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use crate::curve::{EcdsaCurve, Secp256k1};
+use crate::ecdsa_key::{PrvKeyBytes, PubKeyBytes, KEY_SIZE};
+use crate::fixed_bytes::FixedBytes;
+use crate::local_macro::fixed_bytes;
+use crate::ExtendError;
+use core::marker::PhantomData;
+use sha2::{Digest, Sha256};
+
+pub const SIGNATURE_SIZE: usize = KEY_SIZE * 2;
+
+/// ECDSA signature in the compact form `r || s`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Signature<C: EcdsaCurve = Secp256k1>([u8; SIGNATURE_SIZE], PhantomData<C>);
+fixed_bytes!(Signature<C: EcdsaCurve>);
+
+impl<C: EcdsaCurve> Signature<C> {
+    pub fn r(&self) -> &[u8] {
+        &self.0[..KEY_SIZE]
+    }
+
+    pub fn s(&self) -> &[u8] {
+        &self.0[KEY_SIZE..]
+    }
+
+    pub fn is_low_s(&self) -> Result<bool, ExtendError> {
+        Ok(C::normalize_s(&self.0)?.is_none())
+    }
+
+    /// Same signature with `s` replaced by `n - s` if it is high.
+    pub fn normalize_s(&self) -> Result<Self, ExtendError> {
+        let normalized = C::normalize_s(&self.0)?.unwrap_or(self.0);
+        Ok(Self(normalized, PhantomData))
+    }
+
+    pub fn to_der(&self) -> Result<Vec<u8>, ExtendError> {
+        C::to_der(&self.0)
+    }
+
+    pub fn from_der(der: &[u8]) -> Result<Self, ExtendError> {
+        Ok(Self(C::from_der(der)?, PhantomData))
+    }
+}
+
+/// Signature with the recovery id (0 to 3), which tells the parity of `R.y`
+/// and whether `R.x` was not less than the order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RecoverableSignature<C: EcdsaCurve = Secp256k1> {
+    pub signature: Signature<C>,
+    pub recovery_id: u8,
+}
+
+impl<C: EcdsaCurve> RecoverableSignature<C> {
+    /// `r || s || recovery_id`
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_SIZE + 1] {
+        let mut bs = [0; SIGNATURE_SIZE + 1];
+        bs[..SIGNATURE_SIZE].copy_from_slice(self.signature.as_ref());
+        bs[SIGNATURE_SIZE] = self.recovery_id;
+        bs
+    }
+
+    pub fn recover_prehash(&self, digest: &[u8; 32]) -> Result<PubKeyBytes<C>, ExtendError> {
+        let key = C::recover_prehash(digest, self.signature.as_ref(), self.recovery_id)?;
+        key.as_ref().try_into()
+    }
+
+    /// Recovers the public key which signed the SHA-256 of the message.
+    pub fn recover(&self, msg: &[u8]) -> Result<PubKeyBytes<C>, ExtendError> {
+        self.recover_prehash(&Sha256::digest(msg).into())
+    }
+}
+
+impl<C: EcdsaCurve> TryFrom<&[u8]> for RecoverableSignature<C> {
+    type Error = ExtendError;
+
+    fn try_from(src: &[u8]) -> Result<Self, Self::Error> {
+        match src {
+            [sig @ .., recovery_id] if sig.len() == SIGNATURE_SIZE && *recovery_id < 4 => {
+                Ok(Self {
+                    signature: sig.try_into()?,
+                    recovery_id: *recovery_id,
+                })
+            }
+            _ => Err(ExtendError::InvalidFormat("recoverable signature")),
+        }
+    }
+}
+
+//----------------------------------------------------------------
+
+impl<C: EcdsaCurve> PrvKeyBytes<C> {
+    /// Deterministic ECDSA (RFC 6979) over the digest, normalized to low-S.
+    pub fn sign_prehash_recoverable(
+        &self,
+        digest: &[u8; 32],
+    ) -> Result<RecoverableSignature<C>, ExtendError> {
+        let (sig, recovery_id) = C::sign_prehash(self.as_ref(), digest)?;
+        Ok(RecoverableSignature {
+            signature: Signature(sig, PhantomData),
+            recovery_id,
+        })
+    }
+
+    pub fn sign_prehash(&self, digest: &[u8; 32]) -> Result<Signature<C>, ExtendError> {
+        Ok(self.sign_prehash_recoverable(digest)?.signature)
+    }
+
+    /// Signs the SHA-256 of the message.
+    pub fn sign(&self, msg: &[u8]) -> Result<Signature<C>, ExtendError> {
+        self.sign_prehash(&Sha256::digest(msg).into())
+    }
+
+    pub fn sign_recoverable(&self, msg: &[u8]) -> Result<RecoverableSignature<C>, ExtendError> {
+        self.sign_prehash_recoverable(&Sha256::digest(msg).into())
+    }
+}
+
+impl<C: EcdsaCurve> PubKeyBytes<C> {
+    /// Accepts high-S signatures too. Check `Signature::is_low_s` if malleability matters.
+    pub fn verify_prehash(&self, digest: &[u8; 32], sig: &Signature<C>) -> Result<(), ExtendError> {
+        if C::verify_prehash(self.as_ref(), digest, sig.as_ref()) {
+            Ok(())
+        } else {
+            Err(ExtendError::InvalidSignature)
+        }
+    }
+
+    pub fn verify(&self, msg: &[u8], sig: &Signature<C>) -> Result<(), ExtendError> {
+        self.verify_prehash(&Sha256::digest(msg).into(), sig)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::curve::NistP256;
+    use crate::ecdsa_key::PrvKey;
+    use hex_literal::hex;
+
+    #[test]
+    fn secp256k1_rfc6979() {
+        let mut key = [0; 32];
+        key[31] = 1;
+        let prv: PrvKeyBytes = key.as_ref().try_into().unwrap();
+        let sig = prv.sign(b"Satoshi Nakamoto").unwrap();
+        assert_eq!(
+            sig.as_ref(),
+            hex!(
+                "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8"
+                "2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5"
+            )
+        );
+        let public = prv.get_public().unwrap();
+        assert!(public.verify(b"Satoshi Nakamoto", &sig).is_ok());
+        assert!(public.verify(b"Satoshi", &sig).is_err());
+
+        let der = sig.to_der().unwrap();
+        assert_eq!(der[0], 0x30);
+        assert_eq!(Signature::from_der(&der).unwrap(), sig);
+    }
+
+    #[test]
+    fn secp256k1_high_s() {
+        let prv: PrvKeyBytes =
+            hex!("E8F32E723DECF4051AEFAC8E2C93C9C5B214313817CDB01A1494B917C8436B35")
+                .as_ref()
+                .try_into()
+                .unwrap();
+        let sig = prv.sign(b"high").unwrap();
+        assert!(sig.is_low_s().unwrap());
+
+        // s' = n - s
+        let n = hex!("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+        let mut high = [0; SIGNATURE_SIZE];
+        high[..KEY_SIZE].copy_from_slice(sig.r());
+        let mut borrow = 0;
+        for i in (0..KEY_SIZE).rev() {
+            let d = n[i] as i16 - sig.s()[i] as i16 - borrow;
+            high[KEY_SIZE + i] = d.rem_euclid(256) as u8;
+            borrow = (d < 0) as i16;
+        }
+        let high: Signature = high.as_ref().try_into().unwrap();
+        assert!(!high.is_low_s().unwrap());
+        assert_eq!(high.normalize_s().unwrap(), sig);
+
+        let public = prv.get_public().unwrap();
+        assert!(public.verify(b"high", &high).is_ok());
+        assert!(public.verify(b"low", &high).is_err());
+    }
+
+    // RFC 6979 A.2.5, P-256 with SHA-256, message "sample"
+    #[test]
+    fn nist_p256_rfc6979() {
+        let prv: PrvKeyBytes<NistP256> =
+            hex!("C9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721")
+                .as_ref()
+                .try_into()
+                .unwrap();
+        let expected: Signature<NistP256> = hex!(
+            "EFD48B2AACB6A8FD1140DD9CD45E81D69D2C877B56AAF991C34D0EA84EAF3716"
+            "F7CB1C942D657C41D436C7A1B6E29F65F3E900DBB9AFF4064DC4AB2F843ACDA8"
+        )
+        .as_ref()
+        .try_into()
+        .unwrap();
+        assert!(!expected.is_low_s().unwrap());
+
+        let sig = prv.sign(b"sample").unwrap();
+        assert!(sig.is_low_s().unwrap());
+        assert_eq!(sig, expected.normalize_s().unwrap());
+
+        let public = prv.get_public().unwrap();
+        assert!(public.verify(b"sample", &sig).is_ok());
+        assert!(public.verify(b"sample", &expected).is_ok());
+    }
+
+    #[test]
+    fn recover() {
+        let prv: PrvKeyBytes =
+            hex!("E8F32E723DECF4051AEFAC8E2C93C9C5B214313817CDB01A1494B917C8436B35")
+                .as_ref()
+                .try_into()
+                .unwrap();
+        let public = prv.get_public().unwrap();
+        (0..8u8).for_each(|i| {
+            let msg = [i; 16];
+            let sig = prv.sign_recoverable(&msg).unwrap();
+            assert_eq!(sig.recover(&msg).unwrap(), public);
+            assert_ne!(sig.recover(b"other").unwrap(), public);
+
+            let bs = sig.to_bytes();
+            assert_eq!(RecoverableSignature::try_from(bs.as_ref()).unwrap(), sig);
+
+            // R.x + n is beyond the field for these r, so ids 2 and 3 recover nothing
+            let reduced = RecoverableSignature {
+                recovery_id: sig.recovery_id | 2,
+                ..sig.clone()
+            };
+            let bs = reduced.to_bytes();
+            assert_eq!(
+                RecoverableSignature::try_from(bs.as_ref()).unwrap(),
+                reduced
+            );
+            assert!(reduced.recover(&msg).is_err());
+        });
+        let mut bs = prv.sign_recoverable(b"").unwrap().to_bytes();
+        bs[SIGNATURE_SIZE] = 4;
+        assert!(RecoverableSignature::<Secp256k1>::try_from(bs.as_ref()).is_err());
+    }
+}
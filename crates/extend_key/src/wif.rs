@@ -0,0 +1,164 @@
+use crate::base58::{self, Network};
+use crate::ecdsa_key::{KeyBytes, PrvKeyBytes, KEY_SIZE};
+use crate::extkey::ExtKey;
+use crate::ExtendError;
+use core::fmt;
+use core::str::FromStr;
+use zeroize::Zeroizing;
+
+const COMPRESSED_FLAG: u8 = 0x01;
+
+/// Private key in the Wallet Import Format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wif {
+    pub key: PrvKeyBytes,
+    pub network: Network,
+    /// True if the key is used with the compressed public key.
+    pub compressed: bool,
+}
+
+impl Wif {
+    /// With the compressed public key, as every BIP-0032 key is.
+    pub fn new(key: PrvKeyBytes, network: Network) -> Self {
+        Self {
+            key,
+            network,
+            compressed: true,
+        }
+    }
+
+    pub fn version(network: Network) -> u8 {
+        match network {
+            Network::Mainnet => 0x80,
+            Network::Testnet => 0xef,
+        }
+    }
+
+    fn network_of(version: u8) -> Result<Network, ExtendError> {
+        [Network::Mainnet, Network::Testnet]
+            .into_iter()
+            .find(|n| Self::version(*n) == version)
+            .ok_or(ExtendError::UnknownVersion)
+    }
+}
+
+impl TryFrom<&ExtKey<PrvKeyBytes>> for Wif {
+    type Error = ExtendError;
+
+    /// The network is taken from the SLIP-0132 prefix of the key.
+    fn try_from(src: &ExtKey<PrvKeyBytes>) -> Result<Self, Self::Error> {
+        Ok(Self::new(src.key.clone(), src.prefix.network()?))
+    }
+}
+
+impl fmt::Display for Wif {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Zeroizing::new(vec![Self::version(self.network)]);
+        buf.extend_from_slice(self.key.as_ref());
+        if self.compressed {
+            buf.push(COMPRESSED_FLAG);
+        }
+        let s = base58::encode_check(&buf).map_err(|_| fmt::Error)?;
+        f.write_str(&s)
+    }
+}
+
+impl FromStr for Wif {
+    type Err = ExtendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut buf = Zeroizing::new([0; KEY_SIZE + 2 + 4]); // Add length of checksum
+        let len = base58::decode_check(s, buf.as_mut())?;
+        let (version, key, compressed) = match &buf[..len] {
+            [version, key @ ..] if key.len() == KEY_SIZE => (*version, key, false),
+            [version, key @ .., COMPRESSED_FLAG] if key.len() == KEY_SIZE => (*version, key, true),
+            _ => return Err(ExtendError::InvalidFormat("WIF")),
+        };
+        let key: PrvKeyBytes = key.try_into()?;
+        key.validate()?;
+        Ok(Self {
+            key,
+            network: Self::network_of(version)?,
+            compressed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    fn key() -> PrvKeyBytes {
+        hex!("0C28FCA386C7A227600B2FE50B7CAE11EC86D3BF1FBE471BE89827E19D72AA1D")
+            .as_ref()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn mainnet() {
+        let wif = Wif::new(key(), Network::Mainnet);
+        assert_eq!(
+            wif.to_string(),
+            "KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617"
+        );
+        let uncompressed = Wif {
+            compressed: false,
+            ..wif.clone()
+        };
+        assert_eq!(
+            uncompressed.to_string(),
+            "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ"
+        );
+
+        assert_eq!(wif, wif.to_string().parse().unwrap());
+        assert_eq!(uncompressed, uncompressed.to_string().parse().unwrap());
+    }
+
+    #[test]
+    fn testnet() {
+        let wif = Wif::new(key(), Network::Testnet);
+        let s = wif.to_string();
+        assert!(s.starts_with('c'), "{s}");
+        let decoded: Wif = s.parse().unwrap();
+        assert_eq!(decoded.network, Network::Testnet);
+        assert_eq!(decoded, wif);
+    }
+
+    #[test]
+    fn from_extkey() {
+        let xprv: ExtKey<PrvKeyBytes> = ExtKey::from_seed(
+            base58::Prefix::TPRV,
+            &hex!("000102030405060708090a0b0c0d0e0f"),
+        )
+        .unwrap();
+        let wif = Wif::try_from(&xprv).unwrap();
+        assert_eq!(wif.network, Network::Testnet);
+        assert_eq!(&wif.key, xprv.get_key());
+    }
+
+    #[test]
+    fn invalid() {
+        // Broken checksum
+        assert!("KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98618"
+            .parse::<Wif>()
+            .is_err());
+        // Base58Check of a 32 bytes key without version
+        let s = base58::encode_check(key().as_ref()).unwrap();
+        assert!(s.parse::<Wif>().is_err());
+    }
+
+    #[test]
+    fn out_of_range() {
+        let n = hex!("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+        for key in [[0; KEY_SIZE], n] {
+            let buf = [&[0x80], key.as_ref(), &[COMPRESSED_FLAG]].concat();
+            let s = base58::encode_check(&buf).unwrap();
+            assert!(matches!(
+                s.parse::<Wif>(),
+                Err(ExtendError::InvalidPrivateKey)
+            ));
+        }
+    }
+}
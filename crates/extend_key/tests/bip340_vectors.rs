@@ -0,0 +1,41 @@
+use extend_key::ecdsa_key::PrvKeyBytes;
+use extend_key::schnorr::{SchnorrSignature, XOnlyPubKey};
+
+// https://github.com/bitcoin/bips/blob/master/bip-0340/test-vectors.csv
+const VECTORS: &str = include_str!("data/bip340_test_vectors.csv");
+
+fn decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn check_vectors() {
+    VECTORS.lines().skip(1).for_each(|line| {
+        let cols: Vec<_> = line.split(',').collect();
+        let (index, secret, public, aux, msg, sig, result) = (
+            cols[0], cols[1], cols[2], cols[3], cols[4], cols[5], cols[6],
+        );
+        let msg = decode(msg);
+        let sig = SchnorrSignature::try_from(decode(sig).as_slice()).unwrap();
+
+        if !secret.is_empty() {
+            let prv = PrvKeyBytes::try_from(decode(secret).as_slice()).unwrap();
+            assert_eq!(
+                prv.x_only_public().unwrap().as_ref(),
+                decode(public),
+                "{index}"
+            );
+            let aux: [u8; 32] = decode(aux).try_into().unwrap();
+            assert_eq!(prv.sign_schnorr(&msg, &aux).unwrap(), sig, "{index}");
+        }
+
+        let verified = XOnlyPubKey::try_from(decode(public).as_slice())
+            .unwrap()
+            .verify(&msg, &sig)
+            .is_ok();
+        assert_eq!(verified, result == "TRUE", "{index}");
+    });
+}
@@ -0,0 +1,123 @@
+use extend_key::base58::{Network, Prefix};
+use extend_key::descriptor::Descriptor;
+use extend_key::ecdsa_key::PrvKeyBytes;
+use extend_key::extkey::ExtKey;
+use extend_key::origin::OriginKey;
+
+const WORDS: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+fn master() -> OriginKey<PrvKeyBytes> {
+    let words: Vec<&str> = WORDS.split(' ').collect();
+    let seed = mnemonic::calcseed::to_seed(&words).unwrap();
+    OriginKey::master(ExtKey::from_seed(Prefix::XPRV, &seed).unwrap()).unwrap()
+}
+
+/// Account key expression as `[73c5da0a/84'/0'/0']xpub...`
+fn account(path: &str) -> String {
+    let key = master().derive_child(path.parse().unwrap()).unwrap();
+    key.get_public().unwrap().to_string()
+}
+
+fn check(desc: &str, expected: &[&str]) {
+    let d: Descriptor = desc.parse().unwrap();
+    assert!(d.is_ranged());
+    assert_eq!(d.to_string(), desc);
+    let addresses = d
+        .addresses(0..expected.len() as u32, Network::Mainnet)
+        .unwrap();
+    assert_eq!(addresses, expected);
+
+    let with_checksum = d.to_string_with_checksum().unwrap();
+    assert_eq!(with_checksum.parse::<Descriptor>().unwrap(), d);
+}
+
+#[test]
+fn bip44() {
+    let key = account("m/44'/0'/0'");
+    assert!(key.starts_with("[73c5da0a/44'/0'/0']xpub"));
+    check(
+        &format!("pkh({key}/0/*)"),
+        &[
+            "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA",
+            "1Ak8PffB2meyfYnbXZR9EGfLfFZVpzJvQP",
+        ],
+    );
+}
+
+#[test]
+fn bip49() {
+    let key = account("m/49'/0'/0'");
+    check(
+        &format!("sh(wpkh({key}/0/*))"),
+        &["37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf"],
+    );
+}
+
+#[test]
+fn bip84() {
+    let key = account("m/84'/0'/0'");
+    check(
+        &format!("wpkh({key}/0/*)"),
+        &[
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu",
+            "bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g",
+        ],
+    );
+}
+
+#[test]
+fn bip86() {
+    let key = account("m/86'/0'/0'");
+    check(
+        &format!("tr({key}/0/*)"),
+        &[
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            "bc1p4qhjn9zdvkux4e44uhx8tc55attvtyu358kutcqkudyccelu0was9fqzwh",
+        ],
+    );
+}
+
+#[test]
+fn private_and_hardened() {
+    let xprv = master().key.to_string();
+    let d: Descriptor = format!("wpkh([73c5da0a]{xprv}/84h/0h/0h/0/*)")
+        .parse()
+        .unwrap();
+    assert_eq!(
+        d.address(0, Network::Mainnet).unwrap(),
+        "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+    );
+
+    let key = account("m/84'/0'/0'");
+    let d: Descriptor = format!("wpkh({key}/0/*')").parse().unwrap();
+    assert!(d.address(0, Network::Mainnet).is_err());
+}
+
+#[test]
+fn multisig() {
+    let a = account("m/48'/0'/0'/2'");
+    let b = account("m/48'/0'/1'/2'");
+    let sorted: Descriptor = format!("wsh(sortedmulti(2,{a}/0/*,{b}/0/*))")
+        .parse()
+        .unwrap();
+    let reversed: Descriptor = format!("wsh(sortedmulti(2,{b}/0/*,{a}/0/*))")
+        .parse()
+        .unwrap();
+    assert_eq!(
+        sorted.script_pubkeys(0..3).unwrap(),
+        reversed.script_pubkeys(0..3).unwrap()
+    );
+
+    let unsorted: Descriptor = format!("sh(wsh(multi(2,{a}/0/*,{b}/0/*)))")
+        .parse()
+        .unwrap();
+    assert_eq!(
+        unsorted.address(0, Network::Mainnet).unwrap(),
+        "3PkigvgGWiKFLmqc5rpHtHfSTJd2EMeCo7"
+    );
+    assert_eq!(
+        unsorted.address(0, Network::Testnet).unwrap(),
+        "2NFJvkfcJ8ApbYZU9kzSAWEehfeqC2rfEHQ"
+    );
+}
@@ -0,0 +1,153 @@
+use extend_key::base58::Prefix;
+use extend_key::ecdsa_key::{PrvKey, PrvKeyBytes, PubKeyBytes};
+use extend_key::extkey::ExtKey;
+use extend_key::musig::{AggNonce, KeyAggContext, SecNonce, Session};
+use extend_key::ExtendError;
+use hex_literal::hex;
+
+struct Participant {
+    sk: PrvKeyBytes,
+    pk: PubKeyBytes,
+}
+
+fn participants() -> Vec<Participant> {
+    (1u8..=3)
+        .map(|i| {
+            let master: ExtKey<PrvKeyBytes> = ExtKey::from_seed(Prefix::XPRV, &[i; 32]).unwrap();
+            let key = master
+                .derive_child("m/48'/0'/0'/2'/0/0".parse().unwrap())
+                .unwrap();
+            Participant {
+                pk: key.key.get_public().unwrap(),
+                sk: key.key,
+            }
+        })
+        .collect()
+}
+
+fn sign(ctx: &KeyAggContext, signers: &[Participant], msg: &[u8]) -> Result<(), ExtendError> {
+    let aggpk = ctx.x_only()?;
+    let nonces = signers
+        .iter()
+        .map(|p| SecNonce::generate(&p.pk, Some(&p.sk), Some(&aggpk), Some(msg), None))
+        .collect::<Result<Vec<_>, _>>()?;
+    let pubnonces: Vec<_> = nonces.iter().map(|(_, n)| n.clone()).collect();
+    let session = Session::new(ctx, &AggNonce::aggregate(&pubnonces)?, msg)?;
+
+    let mut psigs = vec![];
+    for ((secnonce, pubnonce), p) in nonces.into_iter().zip(signers) {
+        let psig = session.sign(secnonce, &p.sk)?;
+        session.verify(&psig, &pubnonce, &p.pk)?;
+        psigs.push(psig);
+    }
+    // The partial signature of another signer does not verify
+    assert!(session
+        .verify(&psigs[0], &pubnonces[1], &signers[1].pk)
+        .is_err());
+
+    let sig = session.aggregate(&psigs)?;
+    aggpk.verify(msg, &sig)?;
+    assert!(aggpk.verify(b"other message", &sig).is_err());
+    Ok(())
+}
+
+#[test]
+fn simulated_signing() {
+    let signers = participants();
+    let keys: Vec<_> = signers.iter().map(|p| p.pk.clone()).collect();
+    let ctx = KeyAggContext::new(&KeyAggContext::key_sort(&keys)).unwrap();
+    let msg = [0x5a; 32];
+
+    sign(&ctx, &signers, &msg).unwrap();
+    sign(&ctx.tap_tweak(None).unwrap(), &signers, &msg).unwrap();
+    sign(&ctx.tap_tweak(Some(&[7; 32])).unwrap(), &signers, b"").unwrap();
+
+    let derived = ctx.derive(&"m/0/5".parse().unwrap()).unwrap();
+    sign(&derived.tap_tweak(None).unwrap(), &signers, &msg).unwrap();
+}
+
+#[test]
+fn tap_tweak_matches_single_key() {
+    let signers = participants();
+    let keys: Vec<_> = signers.iter().map(|p| p.pk.clone()).collect();
+    let ctx = KeyAggContext::new(&keys).unwrap();
+    let (output, _) = ctx.x_only().unwrap().tap_tweak(None).unwrap();
+    assert_eq!(ctx.tap_tweak(None).unwrap().x_only().unwrap(), output);
+}
+
+#[test]
+fn bip328_xpub() {
+    let signers = participants();
+    let keys: Vec<_> = signers.iter().map(|p| p.pk.clone()).collect();
+    let ctx = KeyAggContext::new(&keys).unwrap();
+
+    let xpub = ctx.xpub();
+    assert_eq!(xpub.key, ctx.aggregate_key().unwrap());
+    assert_eq!(
+        xpub.chain_code.as_ref(),
+        sha256(b"MuSig2MuSig2MuSig2").as_slice()
+    );
+    assert_eq!(xpub.depth.as_ref(), [0]);
+
+    let derived = ctx.derive(&"m/0/5".parse().unwrap()).unwrap();
+    let expected = xpub
+        .get_child_normal_only(0.into())
+        .and_then(|k| k.get_child_normal_only(5.into()))
+        .unwrap();
+    assert_eq!(derived.xpub(), &expected);
+    assert_eq!(derived.aggregate_key().unwrap(), expected.key);
+
+    assert!(matches!(
+        ctx.derive(&"m/0'".parse().unwrap()),
+        Err(ExtendError::HardenedFromPublic)
+    ));
+
+    // Derivation must come before the other tweaks
+    for tweaked in [
+        ctx.tap_tweak(None).unwrap(),
+        ctx.tweak(&[1; 32], false).unwrap(),
+        derived.clone(),
+    ] {
+        assert!(matches!(
+            tweaked.get_child(0.into()),
+            Err(ExtendError::AlreadyTweaked)
+        ));
+    }
+}
+
+#[test]
+fn bip328_vector() {
+    let keys: Vec<PubKeyBytes> = [
+        hex!("03935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9"),
+        hex!("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"),
+    ]
+    .iter()
+    .map(|k| k.as_ref().try_into().unwrap())
+    .collect();
+    let ctx = KeyAggContext::new(&keys).unwrap();
+    assert_eq!(
+        ctx.aggregate_key().unwrap().as_ref(),
+        hex!("0354240c76b8f2999143301a99c7f721ee57eee0bce401df3afeaa9ae218c70f23")
+    );
+    assert_eq!(ctx.xpub().to_string(), "xpub661MyMwAqRbcFt6tk3uaczE1y6EvM1TqXvawXcYmFEWijEM4PDBnuCXwwXEKGEouzXE6QLLRxjatMcLLzJ5LV5Nib1BN7vJg6yp45yHHRbm");
+}
+
+#[test]
+fn not_a_signer() {
+    let signers = participants();
+    let keys: Vec<_> = signers[..2].iter().map(|p| p.pk.clone()).collect();
+    let ctx = KeyAggContext::new(&keys).unwrap();
+    let outsider = &signers[2];
+    let (secnonce, pubnonce) =
+        SecNonce::generate(&outsider.pk, Some(&outsider.sk), None, None, None).unwrap();
+    let session = Session::new(&ctx, &AggNonce::aggregate(&[pubnonce]).unwrap(), b"msg").unwrap();
+    assert!(matches!(
+        session.sign(secnonce, &outsider.sk),
+        Err(ExtendError::NotASigner)
+    ));
+}
+
+fn sha256(data: &[u8]) -> Vec<u8> {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(data).to_vec()
+}
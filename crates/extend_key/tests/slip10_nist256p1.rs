@@ -0,0 +1,119 @@
+use extend_key::base58::Prefix;
+use extend_key::curve::NistP256;
+use extend_key::ecdsa_key::PrvKeyBytes;
+use extend_key::extkey::ExtKey;
+use extend_key::fixed_bytes::FixedBytes;
+use hdpath::path::HDPath;
+use hex_literal::hex;
+
+type NistPrvKey = ExtKey<PrvKeyBytes<NistP256>>;
+
+// https://github.com/satoshilabs/slips/blob/master/slip-0010.md#test-vector-1-for-nist256p1
+struct Chain {
+    fingerprint: [u8; 4],
+    chain_code: [u8; 32],
+    private: [u8; 32],
+    public: [u8; 33],
+}
+
+fn check_vector(seed: &[u8], chain: &[Chain], hdpath: &HDPath) {
+    let m = NistPrvKey::from_seed(Prefix::XPRV, seed).unwrap();
+
+    let check = |key: &NistPrvKey, expected: &Chain| {
+        assert_eq!(key.parent.as_ref(), expected.fingerprint);
+        assert_eq!(key.chain_code.as_ref(), expected.chain_code);
+        assert_eq!(key.key.as_ref(), expected.private);
+        let public = key.get_public().unwrap();
+        assert_eq!(public.key.copy_bytes().as_ref(), expected.public);
+    };
+
+    check(&m, &chain[0]);
+    hdpath
+        .nodes()
+        .iter()
+        .zip(&chain[1..])
+        .fold(m, |parent, (node, expected)| {
+            let child = parent.get_child(*node).unwrap();
+            check(&child, expected);
+            if node.is_normal() {
+                let public = parent.get_public().unwrap().get_child_normal_only(*node);
+                assert_eq!(public.unwrap().key, child.get_public().unwrap().key);
+            }
+            child
+        });
+}
+
+#[test]
+fn check_vector1() {
+    let chain = [
+        Chain {
+            fingerprint: hex!("00000000"),
+            chain_code: hex!("beeb672fe4621673f722f38529c07392fecaa61015c80c34f29ce8b41b3cb6ea"),
+            private: hex!("612091aaa12e22dd2abef664f8a01a82cae99ad7441b7ef8110424915c268bc2"),
+            public: hex!("0266874dc6ade47b3ecd096745ca09bcd29638dd52c2c12117b11ed3e458cfa9e8"),
+        },
+        Chain {
+            fingerprint: hex!("be6105b5"),
+            chain_code: hex!("3460cea53e6a6bb5fb391eeef3237ffd8724bf0a40e94943c98b83825342ee11"),
+            private: hex!("6939694369114c67917a182c59ddb8cafc3004e63ca5d3b84403ba8613debc0c"),
+            public: hex!("0384610f5ecffe8fda089363a41f56a5c7ffc1d81b59a612d0d649b2d22355590c"),
+        },
+        Chain {
+            fingerprint: hex!("9b02312f"),
+            chain_code: hex!("4187afff1aafa8445010097fb99d23aee9f599450c7bd140b6826ac22ba21d0c"),
+            private: hex!("284e9d38d07d21e4e281b645089a94f4cf5a5a81369acf151a1c3a57f18b2129"),
+            public: hex!("03526c63f8d0b4bbbf9c80df553fe66742df4676b241dabefdef67733e070f6844"),
+        },
+    ];
+    check_vector(
+        &hex!("000102030405060708090a0b0c0d0e0f"),
+        &chain,
+        &"m/0'/1".parse().unwrap(),
+    );
+}
+
+// https://github.com/satoshilabs/slips/blob/master/slip-0010.md#test-derivation-retry-for-nist256p1
+#[test]
+fn check_derivation_retry() {
+    let chain = [
+        Chain {
+            fingerprint: hex!("00000000"),
+            chain_code: hex!("beeb672fe4621673f722f38529c07392fecaa61015c80c34f29ce8b41b3cb6ea"),
+            private: hex!("612091aaa12e22dd2abef664f8a01a82cae99ad7441b7ef8110424915c268bc2"),
+            public: hex!("0266874dc6ade47b3ecd096745ca09bcd29638dd52c2c12117b11ed3e458cfa9e8"),
+        },
+        Chain {
+            fingerprint: hex!("be6105b5"),
+            chain_code: hex!("e94c8ebe30c2250a14713212f6449b20f3329105ea15b652ca5bdfc68f6c65c2"),
+            private: hex!("06f0db126f023755d0b8d86d4591718a5210dd8d024e3e14b6159d63f53aa669"),
+            public: hex!("02519b5554a4872e8c9c1c847115363051ec43e93400e030ba3c36b52a3e70a5b7"),
+        },
+        Chain {
+            fingerprint: hex!("3e2b7bc6"),
+            chain_code: hex!("9e87fe95031f14736774cd82f25fd885065cb7c358c1edf813c72af535e83071"),
+            private: hex!("092154eed4af83e078ff9b84322015aefe5769e31270f62c3f66c33888335f3a"),
+            public: hex!("0235bfee614c0d5b2cae260000bb1d0d84b270099ad790022c1ae0b2e782efe120"),
+        },
+    ];
+    check_vector(
+        &hex!("000102030405060708090a0b0c0d0e0f"),
+        &chain,
+        &"m/28578'/33941".parse().unwrap(),
+    );
+}
+
+// https://github.com/satoshilabs/slips/blob/master/slip-0010.md#test-seed-retry-for-nist256p1
+#[test]
+fn check_seed_retry() {
+    let chain = [Chain {
+        fingerprint: hex!("00000000"),
+        chain_code: hex!("7762f9729fed06121fd13f326884c82f59aa95c57ac492ce8c9654e60efd130c"),
+        private: hex!("3b8c18469a4634517d6d0b65448f8e6c62091b45540a1743c5846be55d47d88f"),
+        public: hex!("0383619fadcde31063d8c5cb00dbfe1713f3e6fa169d8541a798752a1c1ca0cb20"),
+    }];
+    check_vector(
+        &hex!("a7305bc8df8d0951f0cb224c0e95d7707cbdf2c6ce7e8d481fec69c7ff5e9446"),
+        &chain,
+        &"m/0'".parse().unwrap(),
+    );
+}
@@ -0,0 +1,264 @@
+use core::fmt;
+use core::str::FromStr;
+
+use crate::coin::CoinType;
+use crate::node::{Node, SIGN_HARDENED};
+use crate::path::{HDPath, HDPathError};
+
+const DEPTH: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Purpose {
+    /// P2PKH (BIP44)
+    Bip44,
+    /// P2WPKH nested in P2SH (BIP49)
+    Bip49,
+    /// Native P2WPKH (BIP84)
+    Bip84,
+    /// P2TR (BIP86)
+    Bip86,
+}
+
+impl Purpose {
+    pub fn index(&self) -> u32 {
+        match self {
+            Purpose::Bip44 => 44,
+            Purpose::Bip49 => 49,
+            Purpose::Bip84 => 84,
+            Purpose::Bip86 => 86,
+        }
+    }
+}
+
+impl TryFrom<u32> for Purpose {
+    type Error = HDPathError;
+
+    fn try_from(index: u32) -> Result<Self, Self::Error> {
+        match index {
+            44 => Ok(Purpose::Bip44),
+            49 => Ok(Purpose::Bip49),
+            84 => Ok(Purpose::Bip84),
+            86 => Ok(Purpose::Bip86),
+            _ => Err(HDPathError::new(format!("unsupported purpose: {index}"))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Change {
+    External,
+    Internal,
+}
+
+impl Change {
+    pub fn index(&self) -> u32 {
+        match self {
+            Change::External => 0,
+            Change::Internal => 1,
+        }
+    }
+}
+
+impl TryFrom<u32> for Change {
+    type Error = HDPathError;
+
+    fn try_from(index: u32) -> Result<Self, Self::Error> {
+        match index {
+            0 => Ok(Change::External),
+            1 => Ok(Change::Internal),
+            _ => Err(HDPathError::new(format!("change must be 0 or 1: {index}"))),
+        }
+    }
+}
+
+//----------------------------------------------------------------
+
+/// `m / purpose' / coin_type' / account' / change / address_index`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bip44Path {
+    purpose: Purpose,
+    coin: CoinType,
+    account: u32,
+    change: Change,
+    index: u32,
+}
+
+impl Bip44Path {
+    pub fn new(purpose: Purpose, coin: CoinType) -> Self {
+        Self {
+            purpose,
+            coin,
+            account: 0,
+            change: Change::External,
+            index: 0,
+        }
+    }
+
+    pub fn with_account(self, account: u32) -> Result<Self, HDPathError> {
+        Ok(Self {
+            account: check_index("account", account)?,
+            ..self
+        })
+    }
+
+    pub fn with_change(self, change: Change) -> Self {
+        Self { change, ..self }
+    }
+
+    pub fn with_index(self, index: u32) -> Result<Self, HDPathError> {
+        Ok(Self {
+            index: check_index("address index", index)?,
+            ..self
+        })
+    }
+
+    #[inline]
+    pub fn purpose(&self) -> Purpose {
+        self.purpose
+    }
+
+    #[inline]
+    pub fn coin(&self) -> CoinType {
+        self.coin
+    }
+
+    #[inline]
+    pub fn account(&self) -> u32 {
+        self.account
+    }
+
+    #[inline]
+    pub fn change(&self) -> Change {
+        self.change
+    }
+
+    #[inline]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn to_hdpath(&self) -> HDPath {
+        self.into()
+    }
+}
+
+fn check_index(target: &str, index: u32) -> Result<u32, HDPathError> {
+    if index >= SIGN_HARDENED {
+        return Err(HDPathError::new(format!("{target} out of range: {index}")));
+    }
+    Ok(index)
+}
+
+impl From<&Bip44Path> for HDPath {
+    fn from(src: &Bip44Path) -> Self {
+        let nodes = vec![
+            Node::Hardened(src.purpose.index()),
+            Node::Hardened(src.coin.index()),
+            Node::Hardened(src.account),
+            Node::Normal(src.change.index()),
+            Node::Normal(src.index),
+        ];
+        nodes.try_into().expect("nodes must not be empty")
+    }
+}
+
+impl TryFrom<&HDPath> for Bip44Path {
+    type Error = HDPathError;
+
+    fn try_from(src: &HDPath) -> Result<Self, Self::Error> {
+        match src.nodes() {
+            [Node::Hardened(purpose), Node::Hardened(coin), Node::Hardened(account), Node::Normal(change), Node::Normal(index)] => {
+                Ok(Bip44Path {
+                    purpose: (*purpose).try_into()?,
+                    coin: CoinType::new(*coin)?,
+                    account: check_index("account", *account)?,
+                    change: (*change).try_into()?,
+                    index: check_index("address index", *index)?,
+                })
+            }
+            nodes if nodes.len() != DEPTH => Err(HDPathError::new(format!(
+                "depth must be {DEPTH}: {}",
+                nodes.len()
+            ))),
+            _ => Err(HDPathError::new(
+                "purpose, coin type and account must be hardened, change and index must not be",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Bip44Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_hdpath().fmt(f)
+    }
+}
+
+impl FromStr for Bip44Path {
+    type Err = HDPathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let path: HDPath = s.parse()?;
+        (&path).try_into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn build() {
+        let path = Bip44Path::new(Purpose::Bip44, CoinType::ETH);
+        assert_eq!("m/44'/60'/0'/0/0", path.to_string());
+
+        let path = Bip44Path::new(Purpose::Bip84, "BTC".parse().unwrap())
+            .with_account(1)
+            .unwrap()
+            .with_change(Change::Internal)
+            .with_index(7)
+            .unwrap();
+        assert_eq!("m/84'/0'/1'/1/7", path.to_string());
+
+        let path = Bip44Path::new(Purpose::Bip86, CoinType::TESTNET);
+        assert_eq!("m/86'/1'/0'/0/0", path.to_string());
+
+        let path = Bip44Path::new(Purpose::Bip49, CoinType::LTC);
+        assert!(path.with_account(SIGN_HARDENED).is_err());
+        assert!(path.with_index(SIGN_HARDENED).is_err());
+    }
+
+    #[test]
+    fn parse_str() {
+        let path: Bip44Path = "m/49'/2'/3'/1/4".parse().unwrap();
+        assert_eq!(Purpose::Bip49, path.purpose());
+        assert_eq!(CoinType::LTC, path.coin());
+        assert_eq!(3, path.account());
+        assert_eq!(Change::Internal, path.change());
+        assert_eq!(4, path.index());
+
+        [
+            "m/44'/60'/0'/0",
+            "m/44'/60'/0'/0/0/0",
+            "m/44/60'/0'/0/0",
+            "m/44'/60/0'/0/0",
+            "m/44'/60'/0/0/0",
+            "m/44'/60'/0'/0'/0",
+            "m/44'/60'/0'/0/0'",
+            "m/45'/60'/0'/0/0",
+            "m/44'/60'/0'/2/0",
+        ]
+        .into_iter()
+        .for_each(|src| {
+            let r: Result<Bip44Path, _> = src.parse();
+            assert!(r.is_err(), "{src}");
+        });
+    }
+
+    #[test]
+    fn hdpath_roundtrip() {
+        let hdpath: HDPath = "m/44'/60'/0'/0/0".parse().unwrap();
+        let path: Bip44Path = (&hdpath).try_into().unwrap();
+        assert_eq!(Bip44Path::new(Purpose::Bip44, CoinType::ETH), path);
+        assert_eq!(hdpath, path.to_hdpath());
+    }
+}
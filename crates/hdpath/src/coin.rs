@@ -0,0 +1,134 @@
+use core::fmt;
+use core::str::FromStr;
+
+use crate::node::SIGN_HARDENED;
+use crate::path::HDPathError;
+
+/// Coin type registered in SLIP-0044.
+/// https://github.com/satoshilabs/slips/blob/master/slip-0044.md
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CoinType(u32);
+
+static REGISTRY: [(CoinType, &str, &str); 17] = [
+    (CoinType::BTC, "BTC", "Bitcoin"),
+    (CoinType::TESTNET, "TESTNET", "Testnet"),
+    (CoinType::LTC, "LTC", "Litecoin"),
+    (CoinType::DOGE, "DOGE", "Dogecoin"),
+    (CoinType::DASH, "DASH", "Dash"),
+    (CoinType::ETH, "ETH", "Ethereum"),
+    (CoinType::ETC, "ETC", "Ether Classic"),
+    (CoinType::ATOM, "ATOM", "Cosmos Hub"),
+    (CoinType::ZEC, "ZEC", "Zcash"),
+    (CoinType::XRP, "XRP", "Ripple"),
+    (CoinType::BCH, "BCH", "Bitcoin Cash"),
+    (CoinType::XLM, "XLM", "Stellar Lumens"),
+    (CoinType::TRX, "TRX", "Tron"),
+    (CoinType::DOT, "DOT", "Polkadot"),
+    (CoinType::SOL, "SOL", "Solana"),
+    (CoinType::BNB, "BNB", "Binance"),
+    (CoinType::ADA, "ADA", "Cardano"),
+];
+
+impl CoinType {
+    pub const BTC: CoinType = CoinType(0);
+    pub const TESTNET: CoinType = CoinType(1);
+    pub const LTC: CoinType = CoinType(2);
+    pub const DOGE: CoinType = CoinType(3);
+    pub const DASH: CoinType = CoinType(5);
+    pub const ETH: CoinType = CoinType(60);
+    pub const ETC: CoinType = CoinType(61);
+    pub const ATOM: CoinType = CoinType(118);
+    pub const ZEC: CoinType = CoinType(133);
+    pub const XRP: CoinType = CoinType(144);
+    pub const BCH: CoinType = CoinType(145);
+    pub const XLM: CoinType = CoinType(148);
+    pub const TRX: CoinType = CoinType(195);
+    pub const DOT: CoinType = CoinType(354);
+    pub const SOL: CoinType = CoinType(501);
+    pub const BNB: CoinType = CoinType(714);
+    pub const ADA: CoinType = CoinType(1815);
+
+    pub fn new(index: u32) -> Result<Self, HDPathError> {
+        if index >= SIGN_HARDENED {
+            return Err(HDPathError::new(format!("coin type out of range: {index}")));
+        }
+        Ok(Self(index))
+    }
+
+    #[inline]
+    pub fn index(&self) -> u32 {
+        self.0
+    }
+
+    pub fn symbol(&self) -> Option<&'static str> {
+        lookup(|(coin, _, _)| coin == self).map(|(_, symbol, _)| symbol)
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        lookup(|(coin, _, _)| coin == self).map(|(_, _, name)| name)
+    }
+}
+
+fn lookup<F>(f: F) -> Option<(CoinType, &'static str, &'static str)>
+where
+    F: Fn(&(CoinType, &'static str, &'static str)) -> bool,
+{
+    REGISTRY.iter().find(|a| f(a)).copied()
+}
+
+impl fmt::Display for CoinType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.symbol() {
+            Some(symbol) => f.write_str(symbol),
+            None => self.0.fmt(f),
+        }
+    }
+}
+
+impl FromStr for CoinType {
+    type Err = HDPathError;
+
+    /// Accepts a registered symbol or name (case insensitive) or a raw index.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(index) = s.parse() {
+            return Self::new(index);
+        }
+        lookup(|(_, symbol, name)| symbol.eq_ignore_ascii_case(s) || name.eq_ignore_ascii_case(s))
+            .map(|(coin, _, _)| coin)
+            .ok_or_else(|| HDPathError::new(format!("unknown coin: {s}")))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn registered() {
+        assert_eq!(0, CoinType::BTC.index());
+        assert_eq!(1, CoinType::TESTNET.index());
+        assert_eq!(60, CoinType::ETH.index());
+        assert_eq!(Some("ETH"), CoinType::ETH.symbol());
+        assert_eq!(Some("Bitcoin"), CoinType::BTC.name());
+        assert_eq!(None, CoinType::new(12345).unwrap().symbol());
+    }
+
+    #[test]
+    fn parse_str() {
+        assert_eq!(CoinType::ETH, "ETH".parse().unwrap());
+        assert_eq!(CoinType::ETH, "eth".parse().unwrap());
+        assert_eq!(CoinType::ETH, "Ethereum".parse().unwrap());
+        assert_eq!(CoinType::LTC, "2".parse().unwrap());
+        assert_eq!(CoinType(12345), "12345".parse().unwrap());
+
+        assert_eq!(None as Option<CoinType>, "".parse().ok());
+        assert_eq!(None as Option<CoinType>, "NOSUCHCOIN".parse().ok());
+        assert_eq!(None as Option<CoinType>, "2147483648".parse().ok());
+    }
+
+    #[test]
+    fn to_string() {
+        assert_eq!("BTC", CoinType::BTC.to_string());
+        assert_eq!("12345", CoinType(12345).to_string());
+    }
+}
@@ -1,2 +1,9 @@
+pub mod bip44;
+pub mod coin;
 pub mod node;
 pub mod path;
+pub mod policy;
+pub mod preset;
+
+#[cfg(feature = "serde")]
+mod serde_impl;
@@ -1,7 +1,8 @@
+use core::fmt;
 use core::str::FromStr;
 
 const HARDENED_CHAR: char = '\'';
-const SIGN_HARDENED: u32 = 1 << 31;
+pub(crate) const SIGN_HARDENED: u32 = 1 << 31;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Node {
@@ -18,10 +19,7 @@ impl Node {
     }
 
     pub fn is_hardened(&self) -> bool {
-        match self {
-            Node::Hardened(_) => true,
-            _ => false,
-        }
+        matches!(self, Node::Hardened(_))
     }
 
     pub fn is_normal(&self) -> bool {
@@ -46,6 +44,15 @@ impl From<u32> for Node {
     }
 }
 
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Node::Normal(index) => write!(f, "{index}"),
+            Node::Hardened(index) => write!(f, "{index}{HARDENED_CHAR}"),
+        }
+    }
+}
+
 impl FromStr for Node {
     type Err = core::num::ParseIntError;
 
@@ -95,4 +102,12 @@ mod test {
         assert_eq!(Node::Hardened(0), "0'".parse().unwrap());
         assert_eq!(Node::Hardened(123), "123'".parse().unwrap());
     }
+
+    #[test]
+    fn to_string() {
+        assert_eq!("0", Node::Normal(0).to_string());
+        assert_eq!("123", Node::Normal(123).to_string());
+        assert_eq!("0'", Node::Hardened(0).to_string());
+        assert_eq!("44'", Node::Hardened(44).to_string());
+    }
 }
@@ -1,3 +1,4 @@
+use core::fmt;
 use core::str::FromStr;
 
 use crate::node::Node;
@@ -10,12 +11,22 @@ pub struct HDPathError {
     reason: String,
 }
 
+impl HDPathError {
+    pub(crate) fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
 impl std::fmt::Display for HDPathError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.reason.fmt(f)
     }
 }
 
+impl std::error::Error for HDPathError {}
+
 impl From<<Node as FromStr>::Err> for HDPathError {
     fn from(src: <Node as FromStr>::Err) -> Self {
         Self {
@@ -47,6 +58,15 @@ impl TryFrom<Vec<Node>> for HDPath {
     }
 }
 
+impl fmt::Display for HDPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{ROOT_CHAR}")?;
+        self.0
+            .iter()
+            .try_for_each(|node| write!(f, "{PATH_SEPARATOR}{node}"))
+    }
+}
+
 impl FromStr for HDPath {
     type Err = HDPathError;
 
@@ -64,10 +84,7 @@ impl FromStr for HDPath {
 }
 
 fn split(s: &str) -> Result<Vec<Node>, <Node as FromStr>::Err> {
-    s.split(PATH_SEPARATOR)
-        .into_iter()
-        .map(Node::from_str)
-        .collect()
+    s.split(PATH_SEPARATOR).map(Node::from_str).collect()
 }
 
 #[cfg(test)]
@@ -109,4 +126,14 @@ mod test {
         assert_eq!(None as Option<HDPath>, "m/".parse().ok());
         assert_eq!(None as Option<HDPath>, "".parse().ok());
     }
+
+    #[test]
+    fn to_string() {
+        ["m/1/2/3/4", "m/1'/2'/3", "m/44'/60'/0'/0/0", "m/0"]
+            .into_iter()
+            .for_each(|src| {
+                let path: HDPath = src.parse().unwrap();
+                assert_eq!(src, path.to_string());
+            });
+    }
 }
@@ -0,0 +1,206 @@
+use core::fmt;
+
+use crate::node::Node;
+use crate::path::{HDPath, HDPathError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// The first n levels must be hardened.
+    HardenedPrefix(usize),
+    /// Levels after the first n must not be hardened.
+    NormalAfter(usize),
+    MinDepth(usize),
+    MaxDepth(usize),
+    /// Every index must be below the value (hardening excluded).
+    IndexBelow(u32),
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::HardenedPrefix(n) => write!(f, "first {n} levels must be hardened"),
+            Rule::NormalAfter(n) => write!(f, "levels after {n} must not be hardened"),
+            Rule::MinDepth(n) => write!(f, "depth must be at least {n}"),
+            Rule::MaxDepth(n) => write!(f, "depth must be at most {n}"),
+            Rule::IndexBelow(n) => write!(f, "index must be below {n}"),
+        }
+    }
+}
+
+/// A rule broken by a path. `level` is the 0-based position of the offending node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Violation {
+    pub rule: Rule,
+    pub level: Option<usize>,
+    pub node: Option<Node>,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.level, self.node) {
+            (Some(level), Some(node)) => write!(f, "{} (level {level}: {node})", self.rule),
+            _ => self.rule.fmt(f),
+        }
+    }
+}
+
+//----------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PathPolicy(Vec<Rule>);
+
+impl PathPolicy {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        Self(rules)
+    }
+
+    /// `purpose' / coin_type' / account' / change / address_index`
+    pub fn bip44() -> Self {
+        Self(vec![
+            Rule::HardenedPrefix(3),
+            Rule::NormalAfter(3),
+            Rule::MaxDepth(5),
+        ])
+    }
+
+    pub fn with(mut self, rule: Rule) -> Self {
+        self.0.push(rule);
+        self
+    }
+
+    #[inline]
+    pub fn rules(&self) -> &[Rule] {
+        &self.0
+    }
+
+    pub fn violations(&self, path: &HDPath) -> Vec<Violation> {
+        self.0
+            .iter()
+            .flat_map(|rule| check_rule(*rule, path.nodes()))
+            .collect()
+    }
+
+    pub fn check(&self, path: &HDPath) -> Result<(), HDPathError> {
+        let violations = self.violations(path);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let reasons: Vec<_> = violations.iter().map(|v| v.to_string()).collect();
+        Err(HDPathError::new(format!(
+            "{path} violates policy: {}",
+            reasons.join(", ")
+        )))
+    }
+}
+
+fn check_rule(rule: Rule, nodes: &[Node]) -> Vec<Violation> {
+    let on_nodes = |pred: &dyn Fn(usize, &Node) -> bool| -> Vec<Violation> {
+        nodes
+            .iter()
+            .enumerate()
+            .filter(|(level, node)| pred(*level, node))
+            .map(|(level, node)| Violation {
+                rule,
+                level: Some(level),
+                node: Some(*node),
+            })
+            .collect()
+    };
+    let on_path = |broken: bool| -> Vec<Violation> {
+        if broken {
+            vec![Violation {
+                rule,
+                level: None,
+                node: None,
+            }]
+        } else {
+            vec![]
+        }
+    };
+
+    match rule {
+        Rule::HardenedPrefix(n) => on_nodes(&|level, node| level < n && node.is_normal()),
+        Rule::NormalAfter(n) => on_nodes(&|level, node| level >= n && node.is_hardened()),
+        Rule::IndexBelow(max) => on_nodes(&|_, node| match node {
+            Node::Normal(i) | Node::Hardened(i) => *i >= max,
+        }),
+        Rule::MinDepth(n) => on_path(nodes.len() < n),
+        Rule::MaxDepth(n) => on_path(nodes.len() > n),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn path(s: &str) -> HDPath {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn bip44() {
+        let policy = PathPolicy::bip44();
+        assert!(policy.check(&path("m/44'/60'/0'/0/0")).is_ok());
+        assert!(policy.check(&path("m/44'/60'/0'")).is_ok());
+
+        assert_eq!(
+            policy.violations(&path("m/44'/60'/0/0/0")),
+            vec![Violation {
+                rule: Rule::HardenedPrefix(3),
+                level: Some(2),
+                node: Some(Node::Normal(0)),
+            }]
+        );
+        assert_eq!(
+            policy.violations(&path("m/44'/60'/0'/0/0'/1")),
+            vec![
+                Violation {
+                    rule: Rule::NormalAfter(3),
+                    level: Some(4),
+                    node: Some(Node::Hardened(0)),
+                },
+                Violation {
+                    rule: Rule::MaxDepth(5),
+                    level: None,
+                    node: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn index_and_depth() {
+        let policy = PathPolicy::default()
+            .with(Rule::MinDepth(2))
+            .with(Rule::IndexBelow(1 << 20));
+        assert!(policy.check(&path("m/0'/1048575")).is_ok());
+        assert_eq!(
+            policy.violations(&path("m/1048576'")),
+            vec![
+                Violation {
+                    rule: Rule::MinDepth(2),
+                    level: None,
+                    node: None,
+                },
+                Violation {
+                    rule: Rule::IndexBelow(1 << 20),
+                    level: Some(0),
+                    node: Some(Node::Hardened(1 << 20)),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn explain() {
+        let err = PathPolicy::bip44()
+            .check(&path("m/44'/60'/0/0'"))
+            .unwrap_err();
+        assert_eq!(
+            "m/44'/60'/0/0' violates policy: \
+             first 3 levels must be hardened (level 2: 0), \
+             levels after 3 must not be hardened (level 3: 0')",
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use core::fmt;
+use core::str::FromStr;
+
+use crate::node::SIGN_HARDENED;
+use crate::path::{HDPath, HDPathError};
+
+const INDEX_PLACEHOLDER: &str = "{i}";
+
+/// Derivation layouts used by popular wallets.
+/// Each template has an `{i}` placeholder for the account index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WalletPreset {
+    LedgerLive,
+    LedgerLegacy,
+    MetaMask,
+    Trezor,
+    MyEtherWallet,
+    ElectrumStandard,
+    ElectrumSegwit,
+    BitcoinCore,
+}
+
+impl WalletPreset {
+    pub const ALL: [WalletPreset; 8] = [
+        WalletPreset::LedgerLive,
+        WalletPreset::LedgerLegacy,
+        WalletPreset::MetaMask,
+        WalletPreset::Trezor,
+        WalletPreset::MyEtherWallet,
+        WalletPreset::ElectrumStandard,
+        WalletPreset::ElectrumSegwit,
+        WalletPreset::BitcoinCore,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WalletPreset::LedgerLive => "ledger-live",
+            WalletPreset::LedgerLegacy => "ledger-legacy",
+            WalletPreset::MetaMask => "metamask",
+            WalletPreset::Trezor => "trezor",
+            WalletPreset::MyEtherWallet => "myetherwallet",
+            WalletPreset::ElectrumStandard => "electrum-standard",
+            WalletPreset::ElectrumSegwit => "electrum-segwit",
+            WalletPreset::BitcoinCore => "bitcoin-core",
+        }
+    }
+
+    pub fn template(&self) -> &'static str {
+        match self {
+            WalletPreset::LedgerLive => "m/44'/60'/{i}'/0/0",
+            WalletPreset::LedgerLegacy => "m/44'/60'/0'/{i}",
+            WalletPreset::MetaMask => "m/44'/60'/0'/0/{i}",
+            WalletPreset::Trezor => "m/44'/60'/0'/0/{i}",
+            WalletPreset::MyEtherWallet => "m/44'/60'/0'/0/{i}",
+            WalletPreset::ElectrumStandard => "m/0/{i}",
+            WalletPreset::ElectrumSegwit => "m/0'/0/{i}",
+            WalletPreset::BitcoinCore => "m/0'/0'/{i}'",
+        }
+    }
+
+    pub fn path(&self, index: u32) -> Result<HDPath, HDPathError> {
+        if index >= SIGN_HARDENED {
+            return Err(HDPathError::new(format!("index out of range: {index}")));
+        }
+        self.template()
+            .replace(INDEX_PLACEHOLDER, &index.to_string())
+            .parse()
+    }
+
+    /// Presets whose layout can produce the given path.
+    pub fn detect(path: &HDPath) -> Vec<(WalletPreset, u32)> {
+        let target = path.to_string();
+        Self::ALL
+            .into_iter()
+            .filter_map(|preset| {
+                let (head, tail) = preset.template().split_once(INDEX_PLACEHOLDER)?;
+                let index = target
+                    .strip_prefix(head)?
+                    .strip_suffix(tail)?
+                    .parse()
+                    .ok()?;
+                Some((preset, index))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for WalletPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for WalletPreset {
+    type Err = HDPathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| HDPathError::new(format!("unknown wallet: {s}")))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn paths() {
+        [
+            (WalletPreset::LedgerLive, 3, "m/44'/60'/3'/0/0"),
+            (WalletPreset::LedgerLegacy, 3, "m/44'/60'/0'/3"),
+            (WalletPreset::MetaMask, 3, "m/44'/60'/0'/0/3"),
+            (WalletPreset::Trezor, 0, "m/44'/60'/0'/0/0"),
+            (WalletPreset::ElectrumStandard, 1, "m/0/1"),
+            (WalletPreset::ElectrumSegwit, 1, "m/0'/0/1"),
+            (WalletPreset::BitcoinCore, 2, "m/0'/0'/2'"),
+        ]
+        .into_iter()
+        .for_each(|(preset, index, expected)| {
+            assert_eq!(expected, preset.path(index).unwrap().to_string());
+        });
+
+        assert!(WalletPreset::MetaMask.path(SIGN_HARDENED).is_err());
+    }
+
+    #[test]
+    fn parse_str() {
+        WalletPreset::ALL.into_iter().for_each(|preset| {
+            assert_eq!(preset, preset.to_string().parse().unwrap());
+        });
+        assert_eq!(WalletPreset::MetaMask, "MetaMask".parse().unwrap());
+        assert_eq!(None as Option<WalletPreset>, "unknown".parse().ok());
+    }
+
+    #[test]
+    fn detect() {
+        let path: HDPath = "m/44'/60'/0'/0/5".parse().unwrap();
+        let found = WalletPreset::detect(&path);
+        assert_eq!(
+            found,
+            vec![
+                (WalletPreset::MetaMask, 5),
+                (WalletPreset::Trezor, 5),
+                (WalletPreset::MyEtherWallet, 5),
+            ]
+        );
+
+        let path: HDPath = "m/44'/60'/0'/0/0".parse().unwrap();
+        let found = WalletPreset::detect(&path);
+        assert!(found.contains(&(WalletPreset::LedgerLive, 0)));
+        assert!(found.contains(&(WalletPreset::MetaMask, 0)));
+
+        let path: HDPath = "m/44'/60'/7'/0/0".parse().unwrap();
+        assert_eq!(
+            WalletPreset::detect(&path),
+            vec![(WalletPreset::LedgerLive, 7)]
+        );
+
+        let path: HDPath = "m/84'/0'/0'/0/0".parse().unwrap();
+        assert!(WalletPreset::detect(&path).is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+use core::fmt;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::node::Node;
+use crate::path::HDPath;
+
+impl Serialize for Node {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_u32(self.raw_index())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NodeVisitor;
+
+        impl<'de> de::Visitor<'de> for NodeVisitor {
+            type Value = Node;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("node string or raw index")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_u32<E: de::Error>(self, v: u32) -> Result<Self::Value, E> {
+                Ok(v.into())
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                let index: u32 = v.try_into().map_err(E::custom)?;
+                Ok(index.into())
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(NodeVisitor)
+        } else {
+            deserializer.deserialize_u32(NodeVisitor)
+        }
+    }
+}
+
+impl Serialize for HDPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.collect_seq(self.nodes())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for HDPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PathVisitor;
+
+        impl<'de> de::Visitor<'de> for PathVisitor {
+            type Value = HDPath;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("path string or sequence of raw indices")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut nodes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+                while let Some(node) = seq.next_element::<Node>()? {
+                    nodes.push(node);
+                }
+                nodes.try_into().map_err(de::Error::custom)
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(PathVisitor)
+        } else {
+            deserializer.deserialize_seq(PathVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Token};
+
+    #[test]
+    fn node() {
+        assert_tokens(&Node::Hardened(44).readable(), &[Token::Str("44'")]);
+        assert_tokens(&Node::Normal(5).readable(), &[Token::Str("5")]);
+        assert_tokens(&Node::Hardened(44).compact(), &[Token::U32(0x8000002c)]);
+        assert_tokens(&Node::Normal(5).compact(), &[Token::U32(5)]);
+    }
+
+    #[test]
+    fn path() {
+        let path: HDPath = "m/44'/60'/0'/0/0".parse().unwrap();
+        assert_tokens(&path.clone().readable(), &[Token::Str("m/44'/60'/0'/0/0")]);
+        assert_tokens(
+            &path.compact(),
+            &[
+                Token::Seq { len: Some(5) },
+                Token::U32(0x8000002c),
+                Token::U32(0x8000003c),
+                Token::U32(0x80000000),
+                Token::U32(0),
+                Token::U32(0),
+                Token::SeqEnd,
+            ],
+        );
+        assert_de_tokens_error::<serde_test::Readable<HDPath>>(
+            &[Token::Str("m/")],
+            "cannot parse integer from empty string",
+        );
+    }
+}
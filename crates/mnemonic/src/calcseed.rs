@@ -13,6 +13,7 @@ use std::{
     ops::{Shr, Sub},
 };
 use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroizing;
 
 const EACH_BITS: usize = 11;
 
@@ -30,7 +31,7 @@ fn checksum(bs: &[u8], len_cbits: usize) -> BigUint {
 
 fn validate(mnemonic: &[&str]) -> Result<()> {
     let length = mnemonic.len();
-    if length < 12 || length % 3 != 0 {
+    if length < 12 || !length.is_multiple_of(3) {
         return Result::Err(Error::new(ErrorKind::InvalidInput, "Wrong length of words"));
     }
     let len_cbits = length / 3;
@@ -82,22 +83,23 @@ pub fn to_mnemonic(bs: Bytes) -> Result<Vec<&'static str>> {
     Ok(result)
 }
 
-pub fn to_seed(mnemonic: &[&str]) -> Result<Bytes> {
+/// The seed is wiped on drop.
+pub fn to_seed(mnemonic: &[&str]) -> Result<Zeroizing<Vec<u8>>> {
     to_seed_with_salt(mnemonic, "")
 }
 
-pub fn to_seed_with_salt(mnemonic: &[&str], salt: &str) -> Result<Bytes> {
+pub fn to_seed_with_salt(mnemonic: &[&str], salt: &str) -> Result<Zeroizing<Vec<u8>>> {
     validate(mnemonic)?;
 
-    let mut result = vec![0; 64];
+    let mut result = Zeroizing::new(vec![0; 64]);
 
     pbkdf2::<Hmac<Sha512>>(
-        mnemonic.join(" ").as_bytes(),
-        format!("mnemonic{salt}").nfkd().to_string().as_bytes(),
+        Zeroizing::new(mnemonic.join(" ")).as_bytes(),
+        Zeroizing::new(format!("mnemonic{salt}").nfkd().to_string()).as_bytes(),
         2048,
         &mut result,
     );
-    Ok(result.into())
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -112,7 +114,7 @@ mod test {
             println!("mnemonic: {mnemonic:?}");
             assert_eq!(sample.mnemonic, mnemonic.join(" "));
             let seed = to_seed_with_salt(&mnemonic, "TREZOR").unwrap();
-            assert_eq!(sample.read_seed(), seed);
+            assert_eq!(sample.read_seed(), seed.as_slice());
         });
     }
 